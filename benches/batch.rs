@@ -0,0 +1,75 @@
+//! Compares one multi-circuit proof for N witnesses against N separate proofs.
+//!
+//! Run with `cargo bench --bench batch`.
+
+use halo2_hi::{keygen, prove, prove_many, verify, verify_many, ConstantMode, MyCircuit, Proof};
+use halo2_proofs::{
+    circuit::Value,
+    pasta::{EqAffine, Fp},
+    poly::commitment::Params,
+};
+use std::time::{Duration, Instant};
+
+const K: u32 = 8;
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let now = Instant::now();
+    let out = f();
+    (out, now.elapsed())
+}
+
+fn main() {
+    let params: Params<EqAffine> = Params::new(K);
+    let constant = Fp::from(2);
+    let pk = keygen(&params, ConstantMode::Fixed, constant).unwrap();
+    let vk = pk.get_vk();
+
+    println!(
+        "{:>4} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        "n", "prove sep", "prove batch", "verify sep", "verify batch", "bytes sep", "bytes batch"
+    );
+    for n in [1, 2, 4, 8, 16] {
+        let (circuits, public): (Vec<_>, Vec<_>) = (0..n as u64)
+            .map(|i| {
+                let (a, b, c) = (Fp::from(i), Fp::from(i + 1), Fp::from(i + 2));
+                let circuit = MyCircuit {
+                    constant,
+                    mode: ConstantMode::Fixed,
+                    a: Value::known(a),
+                    b: Value::known(b),
+                    c: Value::known(c),
+                };
+                (circuit, MyCircuit::public_inputs(constant, a, b, c))
+            })
+            .unzip();
+
+        let (separate, prove_separate) = time(|| {
+            circuits
+                .iter()
+                .zip(&public)
+                .map(|(circuit, public)| prove(&params, &pk, circuit.clone(), public).unwrap())
+                .collect::<Vec<_>>()
+        });
+        let (batched, prove_batched) =
+            time(|| prove_many(&params, &pk, &circuits, &public).unwrap());
+
+        let ((), verify_separate) = time(|| {
+            for (proof, public) in separate.iter().zip(&public) {
+                verify(&params, vk, proof, public).unwrap();
+            }
+        });
+        let ((), verify_batched) = time(|| verify_many(&params, vk, &batched, &public).unwrap());
+
+        let separate_bytes: usize = separate.iter().map(|p: &Proof| p.as_bytes().len()).sum();
+        println!(
+            "{:>4} {:>12.2?} {:>12.2?} {:>12.2?} {:>12.2?} {:>12} {:>12}",
+            n,
+            prove_separate,
+            prove_batched,
+            verify_separate,
+            verify_batched,
+            separate_bytes,
+            batched.as_bytes().len()
+        );
+    }
+}
@@ -0,0 +1,1112 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector, TableColumn,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+// advice, fixed(selector), instance column
+
+/// The number of bits covered by one lookup into the range table.
+pub const RANGE_TABLE_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct MyConfig {
+    pub advice: [Column<Advice>; 2],
+    pub instance: Column<Instance>,
+    pub s_mul: Selector,
+    pub s_add: Selector,
+    pub s_sub: Selector,
+    pub s_neg: Selector,
+    pub s_inv: Selector,
+    pub s_div: Selector,
+    pub s_is_zero: Selector,
+    pub s_bool: Selector,
+    pub s_and: Selector,
+    pub s_or: Selector,
+    pub s_xor: Selector,
+    pub s_not: Selector,
+    pub s_bits: Selector,
+    pub s_select: Selector,
+    /// The range table and its gates, if configured with [`MyChip::configure_with_range`].
+    pub range: Option<RangeConfig>,
+}
+
+/// The range table lookup and the gates built on it, used by [`MyChip::range_check`] and
+/// the comparisons.
+#[derive(Clone, Debug)]
+pub struct RangeConfig {
+    pub s_lt: Selector,
+    pub s_range: Selector,
+    pub s_decompose: Selector,
+    pub range_table: TableColumn,
+}
+
+pub struct MyChip<F: FieldExt> {
+    config: MyConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> MyChip<F> {
+    pub fn new(config: MyConfig) -> Self {
+        MyChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip's gates over the given columns.
+    ///
+    /// `constant` is used to load fixed values into the advice columns. The range check and
+    /// the comparisons are left out; see [`MyChip::configure_with_range`].
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+        constant: Column<Fixed>,
+    ) -> MyConfig {
+        // Enable the ability to enforce equality over cells in each column
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+        for adc in &advice {
+            meta.enable_equality(*adc);
+        }
+
+        let s_mul = meta.selector();
+        let s_add = meta.selector();
+        let s_sub = meta.selector();
+        let s_neg = meta.selector();
+        let s_inv = meta.selector();
+        let s_div = meta.selector();
+        let s_is_zero = meta.selector();
+        let s_bool = meta.selector();
+        let s_and = meta.selector();
+        let s_or = meta.selector();
+        let s_xor = meta.selector();
+        let s_not = meta.selector();
+        let s_bits = meta.selector();
+        let s_select = meta.selector();
+
+        meta.create_gate("mul", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_mul = cell.query_selector(s_mul);
+
+            vec![(lhs * rhs - out) * s_mul]
+        });
+
+        meta.create_gate("add", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_add = cell.query_selector(s_add);
+
+            vec![(lhs + rhs - out) * s_add]
+        });
+
+        meta.create_gate("sub", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_sub = cell.query_selector(s_sub);
+
+            vec![(lhs - rhs - out) * s_sub]
+        });
+
+        meta.create_gate("neg", |cell| {
+            let input = cell.query_advice(advice[0], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_neg = cell.query_selector(s_neg);
+
+            vec![(input + out) * s_neg]
+        });
+
+        meta.create_gate("inv", |cell| {
+            let input = cell.query_advice(advice[0], Rotation::cur());
+            let inv = cell.query_advice(advice[1], Rotation::cur());
+            let s_inv = cell.query_selector(s_inv);
+
+            vec![(input * inv - Expression::Constant(F::one())) * s_inv]
+        });
+
+        // The inverse of the denominator is witnessed next to the quotient, so that
+        // a zero denominator cannot satisfy `rhs * out = lhs` with `lhs = 0`.
+        meta.create_gate("div", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let rhs_inv = cell.query_advice(advice[1], Rotation::next());
+            let s_div = cell.query_selector(s_div);
+
+            vec![
+                (rhs.clone() * rhs_inv - Expression::Constant(F::one())) * s_div.clone(),
+                (rhs * out - lhs) * s_div,
+            ]
+        });
+
+        // out = 1 if input == 0 else 0, with `inv` the inverse of a non-zero input.
+        meta.create_gate("is_zero", |cell| {
+            let input = cell.query_advice(advice[0], Rotation::cur());
+            let inv = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_is_zero = cell.query_selector(s_is_zero);
+
+            vec![
+                (input.clone() * out.clone()) * s_is_zero.clone(),
+                (out - Expression::Constant(F::one()) + input * inv) * s_is_zero,
+            ]
+        });
+
+        meta.create_gate("bool", |cell| {
+            let input = cell.query_advice(advice[0], Rotation::cur());
+            let s_bool = cell.query_selector(s_bool);
+
+            vec![bool_check(input) * s_bool]
+        });
+
+        // The binary logic gates share the layout of "mul" and constrain both inputs to
+        // be boolean, which makes the output boolean too.
+        meta.create_gate("and", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_and = cell.query_selector(s_and);
+
+            vec![
+                bool_check(lhs.clone()) * s_and.clone(),
+                bool_check(rhs.clone()) * s_and.clone(),
+                (lhs * rhs - out) * s_and,
+            ]
+        });
+
+        meta.create_gate("or", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_or = cell.query_selector(s_or);
+
+            vec![
+                bool_check(lhs.clone()) * s_or.clone(),
+                bool_check(rhs.clone()) * s_or.clone(),
+                (lhs.clone() + rhs.clone() - lhs * rhs - out) * s_or,
+            ]
+        });
+
+        meta.create_gate("xor", |cell| {
+            let lhs = cell.query_advice(advice[0], Rotation::cur());
+            let rhs = cell.query_advice(advice[1], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_xor = cell.query_selector(s_xor);
+
+            vec![
+                bool_check(lhs.clone()) * s_xor.clone(),
+                bool_check(rhs.clone()) * s_xor.clone(),
+                (lhs.clone() + rhs.clone() - Expression::Constant(F::from(2)) * lhs * rhs - out)
+                    * s_xor,
+            ]
+        });
+
+        meta.create_gate("not", |cell| {
+            let input = cell.query_advice(advice[0], Rotation::cur());
+            let out = cell.query_advice(advice[0], Rotation::next());
+            let s_not = cell.query_selector(s_not);
+
+            let one = Expression::Constant(F::one());
+            vec![
+                bool_check(input.clone()) * s_not.clone(),
+                (one - input - out) * s_not,
+            ]
+        });
+
+        // Most significant bit first: acc' = 2 * acc + bit.
+        meta.create_gate("bits", |cell| {
+            let bit = cell.query_advice(advice[0], Rotation::cur());
+            let acc = cell.query_advice(advice[1], Rotation::cur());
+            let acc_next = cell.query_advice(advice[1], Rotation::next());
+            let s_bits = cell.query_selector(s_bits);
+
+            vec![
+                bool_check(bit.clone()) * s_bits.clone(),
+                (acc_next - Expression::Constant(F::from(2)) * acc - bit) * s_bits,
+            ]
+        });
+
+        // out = cond ? a : b, for a boolean `cond`.
+        meta.create_gate("select", |cell| {
+            let cond = cell.query_advice(advice[0], Rotation::cur());
+            let a = cell.query_advice(advice[1], Rotation::cur());
+            let b = cell.query_advice(advice[0], Rotation::next());
+            let out = cell.query_advice(advice[1], Rotation::next());
+            let s_select = cell.query_selector(s_select);
+
+            vec![
+                bool_check(cond.clone()) * s_select.clone(),
+                (cond * (a - b.clone()) + b - out) * s_select,
+            ]
+        });
+
+        MyConfig {
+            advice,
+            instance,
+            s_mul,
+            s_add,
+            s_sub,
+            s_neg,
+            s_inv,
+            s_div,
+            s_is_zero,
+            s_bool,
+            s_and,
+            s_or,
+            s_xor,
+            s_not,
+            s_bits,
+            s_select,
+            range: None,
+        }
+    }
+
+    /// Configures the chip as [`MyChip::configure`] does, together with the range table
+    /// lookup needed by [`MyChip::range_check`] and the comparisons.
+    pub fn configure_with_range(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+        constant: Column<Fixed>,
+    ) -> MyConfig {
+        let config = Self::configure(meta, advice, instance, constant);
+
+        let s_lt = meta.selector();
+        let s_range = meta.complex_selector();
+        let s_decompose = meta.selector();
+        let range_table = meta.lookup_table_column();
+
+        // With `low` in [0, 2^n) and `a`, `b` in [0, 2^n), the boolean `lt` is 1 exactly
+        // when a - b is negative, i.e. when it has to be lifted by 2^n.
+        meta.create_gate("lt", |cell| {
+            let a = cell.query_advice(advice[0], Rotation::cur());
+            let b = cell.query_advice(advice[1], Rotation::cur());
+            let low = cell.query_advice(advice[0], Rotation::next());
+            let lt = cell.query_advice(advice[1], Rotation::next());
+            let pow2 = cell.query_advice(advice[0], Rotation(2));
+            let s_lt = cell.query_selector(s_lt);
+
+            vec![
+                (a - b + lt.clone() * pow2 - low) * s_lt.clone(),
+                bool_check(lt) * s_lt,
+            ]
+        });
+
+        // Every enabled row of advice[0] must hold a value in [0, 2^RANGE_TABLE_BITS).
+        meta.lookup(|cell| {
+            let value = cell.query_advice(advice[0], Rotation::cur());
+            let s_range = cell.query_selector(s_range);
+
+            vec![(s_range * value, range_table)]
+        });
+
+        // Running sum over limbs: z = limb + 2^RANGE_TABLE_BITS * z'.
+        meta.create_gate("decompose", |cell| {
+            let limb = cell.query_advice(advice[0], Rotation::cur());
+            let z = cell.query_advice(advice[1], Rotation::cur());
+            let z_next = cell.query_advice(advice[1], Rotation::next());
+            let s_decompose = cell.query_selector(s_decompose);
+
+            let radix = Expression::Constant(F::from(1 << RANGE_TABLE_BITS));
+            vec![(z - limb - radix * z_next) * s_decompose]
+        });
+
+        MyConfig {
+            range: Some(RangeConfig {
+                s_lt,
+                s_range,
+                s_decompose,
+                range_table,
+            }),
+            ..config
+        }
+    }
+
+    fn range(&self) -> &RangeConfig {
+        self.config
+            .range
+            .as_ref()
+            .expect("the range check needs MyChip::configure_with_range")
+    }
+}
+
+#[derive(Clone)]
+pub struct Number<F: FieldExt>(pub AssignedCell<F, F>);
+
+impl<F: FieldExt> MyChip<F> {
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| {
+                region
+                    .assign_advice(
+                        || "private input",
+                        self.config.advice[0],
+                        0,
+                        || value,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Loads the public input at `row` of the instance column.
+    pub fn load_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "load public",
+            |mut region| {
+                region
+                    .assign_advice_from_instance(
+                        || "public input",
+                        self.config.instance,
+                        row,
+                        self.config.advice[0],
+                        0,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    pub fn load_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        constant: F,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "load constant",
+            |mut region| {
+                region
+                    .assign_advice_from_constant(
+                        || "constant value",
+                        self.config.advice[0],
+                        0,
+                        constant,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    pub fn mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "mul",
+            |mut region| {
+                self.config.s_mul.enable(&mut region, 0)?;
+
+                // copy cell value to region's advice cell and constrains them to be equal.
+                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+
+                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a * *b));
+                region
+                    .assign_advice(
+                        || "lhs * rhs",
+                        self.config.advice[0],
+                        1,
+                        || value,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                self.config.s_add.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+
+                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a + *b));
+                region
+                    .assign_advice(
+                        || "lhs + rhs",
+                        self.config.advice[0],
+                        1,
+                        || value,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    pub fn sub(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "sub",
+            |mut region| {
+                self.config.s_sub.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+
+                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a - *b));
+                region
+                    .assign_advice(|| "lhs - rhs", self.config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    pub fn neg(&self, mut layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "neg",
+            |mut region| {
+                self.config.s_neg.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "input", &mut region, self.config.advice[0], 0)?;
+
+                let value = a.0.value().map(|a| -*a);
+                region
+                    .assign_advice(|| "-input", self.config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Returns `1 / a`, or `Error::Synthesis` if `a` is known to be zero.
+    pub fn inv(&self, mut layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
+        a.0.value().error_if_known_and(|a| a.is_zero_vartime())?;
+
+        layouter.assign_region(
+            || "inv",
+            |mut region| {
+                self.config.s_inv.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "input", &mut region, self.config.advice[0], 0)?;
+
+                let value = a.0.value().map(|a| a.invert().unwrap());
+                region
+                    .assign_advice(|| "1 / input", self.config.advice[1], 0, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Returns `a / b`, or `Error::Synthesis` if `b` is known to be zero.
+    pub fn div(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        b.0.value().error_if_known_and(|b| b.is_zero_vartime())?;
+
+        layouter.assign_region(
+            || "div",
+            |mut region| {
+                self.config.s_div.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+
+                let rhs_inv = b.0.value().map(|b| b.invert().unwrap());
+                region.assign_advice(|| "1 / rhs", self.config.advice[1], 1, || rhs_inv)?;
+
+                let value = a.0.value().zip(rhs_inv).map(|(a, b)| *a * b);
+                region
+                    .assign_advice(|| "lhs / rhs", self.config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Returns `1` if `a` is zero and `0` otherwise.
+    pub fn is_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "is_zero",
+            |mut region| {
+                self.config.s_is_zero.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "input", &mut region, self.config.advice[0], 0)?;
+
+                let inv = a.0.value().map(|a| a.invert().unwrap_or(F::zero()));
+                region.assign_advice(|| "1 / input", self.config.advice[1], 0, || inv)?;
+
+                let value = a.0.value().map(|a| {
+                    if a.is_zero_vartime() {
+                        F::one()
+                    } else {
+                        F::zero()
+                    }
+                });
+                region
+                    .assign_advice(|| "input == 0", self.config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Fills the range table. Circuits that call [`MyChip::range_check`] must call this
+    /// once, and need at least `2^RANGE_TABLE_BITS` usable rows.
+    pub fn load_range_table(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "range table",
+            |mut table| {
+                for value in 0..1 << RANGE_TABLE_BITS {
+                    table.assign_cell(
+                        || "value",
+                        self.range().range_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Constrains `a` to lie in `[0, 2^bits)`.
+    ///
+    /// `a` is split into `RANGE_TABLE_BITS`-bit limbs, each looked up in the range table.
+    /// A narrower top limb is also looked up shifted left, so that it is bounded by its
+    /// own width rather than the table's.
+    pub fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        bits: usize,
+    ) -> Result<(), Error> {
+        assert!(bits > 0, "range check needs at least one bit");
+        let limbs = bits.div_ceil(RANGE_TABLE_BITS);
+        let top_bits = bits - (limbs - 1) * RANGE_TABLE_BITS;
+
+        layouter.assign_region(
+            || format!("range check {} bits", bits),
+            |mut region| {
+                let radix_inv = F::from(1 << RANGE_TABLE_BITS).invert().unwrap();
+                let mut z =
+                    a.0.copy_advice(|| "z 0", &mut region, self.config.advice[1], 0)?;
+                for row in 0..limbs {
+                    self.range().s_range.enable(&mut region, row)?;
+                    if row + 1 == limbs {
+                        // The top limb is whatever remains, so an out-of-range value
+                        // fails the lookup instead of the running sum.
+                        let limb =
+                            z.copy_advice(|| "top limb", &mut region, self.config.advice[0], row)?;
+                        if top_bits < RANGE_TABLE_BITS {
+                            self.range_shift(&mut region, limb, row + 1, top_bits)?;
+                        }
+                        break;
+                    }
+
+                    self.range().s_decompose.enable(&mut region, row)?;
+                    let limb = z.value().map(|z| F::from(low_bits(z, RANGE_TABLE_BITS)));
+                    region.assign_advice(
+                        || format!("limb {}", row),
+                        self.config.advice[0],
+                        row,
+                        || limb,
+                    )?;
+                    let next = z.value().zip(limb).map(|(z, limb)| (*z - limb) * radix_inv);
+                    z = region.assign_advice(
+                        || format!("z {}", row + 1),
+                        self.config.advice[1],
+                        row + 1,
+                        || next,
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Constrains `a` to be `0` or `1`.
+    pub fn assert_bool(&self, mut layouter: impl Layouter<F>, a: Number<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assert bool",
+            |mut region| {
+                self.config.s_bool.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "input", &mut region, self.config.advice[0], 0)?;
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns `a AND b` for boolean `a` and `b`.
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.logic(layouter, "and", self.config.s_and, a, b, |a, b| a * b)
+    }
+
+    /// Returns `a OR b` for boolean `a` and `b`.
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.logic(layouter, "or", self.config.s_or, a, b, |a, b| a + b - a * b)
+    }
+
+    /// Returns `a XOR b` for boolean `a` and `b`.
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.logic(layouter, "xor", self.config.s_xor, a, b, |a, b| {
+            a + b - F::from(2) * a * b
+        })
+    }
+
+    /// Returns `NOT a` for a boolean `a`.
+    pub fn not(&self, mut layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "not",
+            |mut region| {
+                self.config.s_not.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "input", &mut region, self.config.advice[0], 0)?;
+
+                let value = a.0.value().map(|a| F::one() - *a);
+                region
+                    .assign_advice(|| "not input", self.config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Lays out one of the binary logic gates.
+    fn logic(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        selector: Selector,
+        a: Number<F>,
+        b: Number<F>,
+        op: impl Fn(F, F) -> F,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+
+                let value = a.0.value().zip(b.0.value()).map(|(a, b)| op(*a, *b));
+                region
+                    .assign_advice(
+                        || format!("lhs {} rhs", name),
+                        self.config.advice[0],
+                        1,
+                        || value,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Returns `1` if `a < b` and `0` otherwise.
+    ///
+    /// `a` and `b` are range checked to `bits` bits, which must be less than the field's
+    /// capacity; the range table has to be loaded.
+    pub fn lt(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        bits: usize,
+    ) -> Result<Number<F>, Error> {
+        assert!(
+            bits < F::CAPACITY as usize,
+            "comparison width exceeds the field"
+        );
+        self.range_check(layouter.namespace(|| "range check lhs"), a.clone(), bits)?;
+        self.range_check(layouter.namespace(|| "range check rhs"), b.clone(), bits)?;
+
+        let (low, lt) = layouter.assign_region(
+            || "lt",
+            |mut region| {
+                self.range().s_lt.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+
+                let pow2 = F::from(2).pow(&[bits as u64, 0, 0, 0]);
+                region.assign_advice_from_constant(|| "2^bits", self.config.advice[0], 2, pow2)?;
+
+                let lt = a.0.value().zip(b.0.value()).map(|(a, b)| {
+                    if repr_lt(a, b) {
+                        F::one()
+                    } else {
+                        F::zero()
+                    }
+                });
+                let low = a.0.value().zip(b.0.value()).zip(lt);
+                let low = low.map(|((a, b), lt)| *a - *b + lt * pow2);
+                let low = region.assign_advice(|| "low", self.config.advice[0], 1, || low)?;
+                let lt = region.assign_advice(|| "lhs < rhs", self.config.advice[1], 1, || lt)?;
+                Ok((Number(low), Number(lt)))
+            },
+        )?;
+
+        self.range_check(layouter.namespace(|| "range check low"), low, bits)?;
+        Ok(lt)
+    }
+
+    /// Returns `1` if `a <= b` and `0` otherwise, with the same bounds as [`MyChip::lt`].
+    pub fn le(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        bits: usize,
+    ) -> Result<Number<F>, Error> {
+        let gt = self.lt(layouter.namespace(|| "rhs < lhs"), b, a, bits)?;
+        self.not(layouter.namespace(|| "not rhs < lhs"), gt)
+    }
+
+    /// Returns `1` if `a == b` and `0` otherwise, for `a` and `b` bounded as in
+    /// [`MyChip::lt`]. Use [`MyChip::is_equal`] for arbitrary field elements.
+    pub fn eq(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        bits: usize,
+    ) -> Result<Number<F>, Error> {
+        let lt = self.lt(
+            layouter.namespace(|| "lhs < rhs"),
+            a.clone(),
+            b.clone(),
+            bits,
+        )?;
+        let gt = self.lt(layouter.namespace(|| "rhs < lhs"), b, a, bits)?;
+        let ne = self.add(layouter.namespace(|| "lhs != rhs"), lt, gt)?;
+        self.not(layouter.namespace(|| "lhs == rhs"), ne)
+    }
+
+    /// Returns `1` if `a == b` and `0` otherwise.
+    pub fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let diff = self.sub(layouter.namespace(|| "lhs - rhs"), a, b)?;
+        self.is_zero(layouter.namespace(|| "lhs - rhs == 0"), diff)
+    }
+
+    /// Returns `a` if `cond` is `1` and `b` if it is `0`; any other `cond` fails the
+    /// "select" gate.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: Number<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                self.config.s_select.enable(&mut region, 0)?;
+
+                cond.0
+                    .copy_advice(|| "cond", &mut region, self.config.advice[0], 0)?;
+                a.0.copy_advice(|| "a", &mut region, self.config.advice[1], 0)?;
+                b.0.copy_advice(|| "b", &mut region, self.config.advice[0], 1)?;
+
+                let value = cond.0.value().zip(a.0.value()).zip(b.0.value());
+                let value = value.map(|((cond, a), b)| *cond * (*a - *b) + *b);
+                region
+                    .assign_advice(|| "cond ? a : b", self.config.advice[1], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Returns the smaller of `a` and `b`, with the same bounds as [`MyChip::lt`].
+    pub fn min(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        bits: usize,
+    ) -> Result<Number<F>, Error> {
+        let lt = self.lt(
+            layouter.namespace(|| "lhs < rhs"),
+            a.clone(),
+            b.clone(),
+            bits,
+        )?;
+        self.select(layouter.namespace(|| "min"), lt, a, b)
+    }
+
+    /// Returns the larger of `a` and `b`, with the same bounds as [`MyChip::lt`].
+    pub fn max(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        bits: usize,
+    ) -> Result<Number<F>, Error> {
+        let lt = self.lt(
+            layouter.namespace(|| "lhs < rhs"),
+            a.clone(),
+            b.clone(),
+            bits,
+        )?;
+        self.select(layouter.namespace(|| "max"), lt, b, a)
+    }
+
+    /// Looks up `limb * 2^(RANGE_TABLE_BITS - bits)` at `row`, multiplying with the mul gate.
+    fn range_shift(
+        &self,
+        region: &mut Region<'_, F>,
+        limb: AssignedCell<F, F>,
+        row: usize,
+        bits: usize,
+    ) -> Result<(), Error> {
+        let shift = F::from(1 << (RANGE_TABLE_BITS - bits));
+
+        self.config.s_mul.enable(region, row)?;
+        self.range().s_range.enable(region, row + 1)?;
+
+        limb.copy_advice(|| "top limb", region, self.config.advice[0], row)?;
+        region.assign_advice_from_constant(|| "shift", self.config.advice[1], row, shift)?;
+        let value = limb.value().map(|limb| *limb * shift);
+        region.assign_advice(
+            || "shifted top limb",
+            self.config.advice[0],
+            row + 1,
+            || value,
+        )?;
+        Ok(())
+    }
+
+    /// Decomposes `a` into `num_bits` boolean Numbers, least significant first.
+    ///
+    /// The witness only satisfies the circuit if `a < 2^num_bits`. With
+    /// `num_bits == F::NUM_BITS` the bits are also constrained to encode an integer
+    /// below the modulus, so that every field element has exactly one decomposition.
+    pub fn to_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        num_bits: usize,
+    ) -> Result<Vec<Number<F>>, Error> {
+        assert!(
+            num_bits <= F::NUM_BITS as usize,
+            "cannot decompose into more bits than the field has"
+        );
+        let bits: Vec<_> = (0..num_bits)
+            .rev()
+            .map(|i| a.0.value().map(|a| F::from(bit(a, i) as u64)))
+            .collect();
+
+        let (bits, _) = self.running_sum(
+            layouter.namespace(|| "to bits"),
+            num_bits,
+            Some(&a),
+            |region, row| {
+                region.assign_advice(
+                    || format!("bit {}", num_bits - 1 - row),
+                    self.config.advice[0],
+                    row,
+                    || bits[row],
+                )
+            },
+        )?;
+        if num_bits == F::NUM_BITS as usize {
+            self.assert_canonical(layouter.namespace(|| "canonical"), &bits)?;
+        }
+        Ok(bits.into_iter().rev().collect())
+    }
+
+    /// Returns `sum(bits[i] * 2^i)`, constraining each bit to be boolean.
+    ///
+    /// As in [`MyChip::to_bits`], `F::NUM_BITS` bits must encode an integer below the
+    /// modulus, so the sum never wraps.
+    pub fn from_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Number<F>],
+    ) -> Result<Number<F>, Error> {
+        let n = bits.len();
+        assert!(
+            n <= F::NUM_BITS as usize,
+            "cannot recompose more bits than the field has"
+        );
+        let (bits, acc) = self.running_sum(
+            layouter.namespace(|| "from bits"),
+            n,
+            None,
+            |region, row| {
+                bits[n - 1 - row].0.copy_advice(
+                    || format!("bit {}", n - 1 - row),
+                    region,
+                    self.config.advice[0],
+                    row,
+                )
+            },
+        )?;
+
+        if n == F::NUM_BITS as usize {
+            self.assert_canonical(layouter.namespace(|| "canonical"), &bits)?;
+        }
+        Ok(acc)
+    }
+
+    /// Lays out the "bits" gate over `n` rows, with `bit(region, row)` assigning the
+    /// bit of row `row`, most significant first. Returns the bits and their sum, which
+    /// is constrained to equal `sum` if given.
+    fn running_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: usize,
+        sum: Option<&Number<F>>,
+        mut bit: impl FnMut(&mut Region<'_, F>, usize) -> Result<AssignedCell<F, F>, Error>,
+    ) -> Result<(Vec<Number<F>>, Number<F>), Error> {
+        layouter.assign_region(
+            || "running sum",
+            |mut region| {
+                let mut acc = region.assign_advice_from_constant(
+                    || "acc 0",
+                    self.config.advice[1],
+                    0,
+                    F::zero(),
+                )?;
+                let mut bits = Vec::with_capacity(n);
+                for row in 0..n {
+                    self.config.s_bits.enable(&mut region, row)?;
+
+                    let b = bit(&mut region, row)?;
+                    let value = acc.value().zip(b.value()).map(|(acc, b)| acc.double() + b);
+                    acc = region.assign_advice(
+                        || format!("acc {}", row + 1),
+                        self.config.advice[1],
+                        row + 1,
+                        || value,
+                    )?;
+                    bits.push(Number(b));
+                }
+                if let Some(sum) = sum {
+                    region.constrain_equal(acc.cell(), sum.0.cell())?;
+                }
+                Ok((bits, Number(acc)))
+            },
+        )
+    }
+
+    /// Constrains the bits, most significant first, to encode an integer no larger than
+    /// `p - 1`, scanning for the first position where they differ from it.
+    fn assert_canonical(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Number<F>],
+    ) -> Result<(), Error> {
+        let max = -F::one();
+        let mut prefix_eq = self.load_constant(layouter.namespace(|| "prefix equal"), F::one())?;
+        for (i, b) in bits.iter().enumerate() {
+            let position = bits.len() - 1 - i;
+            let mut layouter = layouter.namespace(|| format!("bit {}", position));
+            if bit(&max, position) {
+                prefix_eq = self.and(layouter, prefix_eq, b.clone())?;
+            } else {
+                // A set bit where p - 1 has a clear one makes the value too large, so
+                // the "and" gate's output is fixed to zero.
+                layouter.assign_region(
+                    || "within bound",
+                    |mut region| {
+                        self.config.s_and.enable(&mut region, 0)?;
+
+                        prefix_eq.0.copy_advice(
+                            || "prefix equal",
+                            &mut region,
+                            self.config.advice[0],
+                            0,
+                        )?;
+                        b.0.copy_advice(|| "bit", &mut region, self.config.advice[1], 0)?;
+                        region.assign_advice_from_constant(
+                            || "zero",
+                            self.config.advice[0],
+                            1,
+                            F::zero(),
+                        )?;
+                        Ok(())
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Number<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.0.cell(), self.config.instance, row)
+    }
+}
+
+/// `x * (1 - x)`, which vanishes exactly when `x` is boolean.
+fn bool_check<F: FieldExt>(x: Expression<F>) -> Expression<F> {
+    x.clone() * (Expression::Constant(F::one()) - x)
+}
+
+/// Bit `i` of `value`'s canonical little-endian representation.
+fn bit<F: FieldExt>(value: &F, i: usize) -> bool {
+    (value.to_repr().as_ref()[i / 8] >> (i % 8)) & 1 == 1
+}
+
+/// The low `bits` bits of `value`'s canonical little-endian representation.
+fn low_bits<F: FieldExt>(value: &F, bits: usize) -> u64 {
+    let repr = value.to_repr();
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&repr.as_ref()[..8]);
+    u64::from_le_bytes(low) & ((1 << bits) - 1)
+}
+
+/// Compares canonical representations as integers.
+fn repr_lt<F: FieldExt>(a: &F, b: &F) -> bool {
+    let (a, b) = (a.to_repr(), b.to_repr());
+    a.as_ref().iter().rev().lt(b.as_ref().iter().rev())
+}
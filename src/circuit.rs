@@ -0,0 +1,191 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    chip::{MyChip, MyConfig},
+    expr::{load_inputs, Expr},
+    instance::{InstanceError, PublicInputs},
+    min_k::min_k,
+    poseidon::{poseidon_hash, PoseidonChip, PoseidonConfig},
+};
+
+// const * a^2 + b * c = d
+// a * a + b * c = d
+// poseidon(a, b, c) = H
+
+/// Columns for `MyChip` and `PoseidonChip`, shared by the circuits that hash.
+#[derive(Clone, Debug)]
+pub struct MyCircuitConfig {
+    pub chip: MyConfig,
+    pub poseidon: PoseidonConfig,
+}
+
+/// The public inputs of `MyCircuit`: the result `d`, the commitment `H` and the
+/// multiplier `constant`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MyPublicInputs<F> {
+    pub d: F,
+    pub h: F,
+    pub constant: F,
+}
+
+impl<F: FieldExt> MyPublicInputs<F> {
+    pub const D: usize = 0;
+    pub const H: usize = 1;
+    pub const CONSTANT: usize = 2;
+
+    /// The public inputs for the witness `a`, `b`, `c`.
+    pub fn new(constant: F, a: F, b: F, c: F) -> Self {
+        MyPublicInputs {
+            d: constant * (a.square() + b * c),
+            h: poseidon_hash(&[a, b, c]),
+            constant,
+        }
+    }
+}
+
+impl<F: FieldExt> PublicInputs<F> for MyPublicInputs<F> {
+    const NAMES: &'static [&'static str] = &["d", "H", "constant"];
+
+    fn to_instance(&self) -> Vec<F> {
+        vec![self.d, self.h, self.constant]
+    }
+
+    fn from_instance(instance: &[F]) -> Result<Self, InstanceError> {
+        Self::check(instance)?;
+        Ok(MyPublicInputs {
+            d: instance[Self::D],
+            h: instance[Self::H],
+            constant: instance[Self::CONSTANT],
+        })
+    }
+}
+
+/// Where `MyCircuit` reads its multiplier from. Both layouts expose the constant as a
+/// public input, but only `Fixed` bakes it into the keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConstantMode {
+    /// The constant is assigned from a fixed column, so each value needs its own key.
+    #[default]
+    Fixed = 0,
+    /// The constant is read from the instance column, so one key works for every value.
+    Public = 1,
+}
+
+#[derive(Default, Clone)]
+pub struct MyCircuit<F: FieldExt> {
+    pub constant: F,
+    pub mode: ConstantMode,
+    pub a: Value<F>,
+    pub b: Value<F>,
+    pub c: Value<F>,
+}
+
+impl<F: FieldExt> MyCircuit<F> {
+    /// The relation proven by this circuit, `constant * (a * a + b * c)`.
+    pub fn expr(constant: F) -> Expr<F> {
+        Self::relation(Expr::constant(constant))
+    }
+
+    fn relation(constant: Expr<F>) -> Expr<F> {
+        let (a, b, c) = (Expr::var("a"), Expr::var("b"), Expr::var("c"));
+        constant * (a.clone() * a + b * c)
+    }
+
+    /// The instance column for a witness, laid out as `MyPublicInputs`.
+    pub fn public_inputs(constant: F, a: F, b: F, c: F) -> Vec<F> {
+        MyPublicInputs::new(constant, a, b, c).to_instance()
+    }
+
+    /// The smallest `k` that the circuit fits in for the given `mode`.
+    pub fn min_k(mode: ConstantMode) -> Result<u32, Error> {
+        let circuit = MyCircuit {
+            constant: F::one(),
+            mode,
+            a: Value::known(F::zero()),
+            b: Value::known(F::zero()),
+            c: Value::known(F::zero()),
+        };
+        let public = Self::public_inputs(F::one(), F::zero(), F::zero(), F::zero());
+        min_k(&circuit, &[public])
+    }
+
+    /// A circuit without a witness, for key generation. `constant` only affects the
+    /// keys in `ConstantMode::Fixed`.
+    pub fn empty(mode: ConstantMode, constant: F) -> Self {
+        MyCircuit {
+            constant,
+            mode,
+            a: Value::unknown(),
+            b: Value::unknown(),
+            c: Value::unknown(),
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = MyCircuitConfig;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::empty(self.mode, self.constant)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let chip = MyChip::configure(meta, advice, instance, constant);
+
+        let state = [(); 3].map(|_| meta.advice_column());
+        let round_constants = [(); 3].map(|_| meta.fixed_column());
+        let poseidon = PoseidonChip::configure(meta, state, round_constants);
+
+        MyCircuitConfig { chip, poseidon }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MyChip::new(config.chip);
+        let poseidon = PoseidonChip::new(config.poseidon);
+
+        let inputs = [("a", self.a), ("b", self.b), ("c", self.c)]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        let mut vars = load_inputs(&chip, &mut layouter, &inputs)?;
+
+        let constant = match self.mode {
+            ConstantMode::Fixed => {
+                let constant =
+                    chip.load_constant(layouter.namespace(|| "load constant"), self.constant)?;
+                chip.expose_public(
+                    layouter.namespace(|| "expose constant"),
+                    constant.clone(),
+                    MyPublicInputs::<F>::CONSTANT,
+                )?;
+                constant
+            }
+            ConstantMode::Public => chip.load_public(
+                layouter.namespace(|| "load constant"),
+                MyPublicInputs::<F>::CONSTANT,
+            )?,
+        };
+        vars.insert("constant".to_string(), constant);
+
+        let relation = Self::relation(Expr::var("constant"));
+        let d = relation.assign(&chip, layouter.namespace(|| "d"), &vars)?;
+        let inputs = [&vars["a"], &vars["b"], &vars["c"]].map(|var| var.clone());
+        let h = poseidon.hash(layouter.namespace(|| "H"), &inputs)?;
+
+        chip.expose_public(layouter.namespace(|| "expose d"), d, MyPublicInputs::<F>::D)?;
+        chip.expose_public(layouter.namespace(|| "expose H"), h, MyPublicInputs::<F>::H)
+    }
+}
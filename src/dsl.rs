@@ -0,0 +1,436 @@
+//! A small textual language for [`Expr`] circuits.
+//!
+//! ```text
+//! // comments run to the end of the line
+//! private a, b, c;
+//! d = 2 * (a^2 + b*c);
+//! public d;
+//! ```
+//!
+//! `private` declares inputs, assignments bind intermediate values, and `public`
+//! exposes values as instance rows in the order they are listed. Expressions use
+//! `+`, `-`, `*`, `/`, unary `-` and `^`, whose exponent must be an integer literal of
+//! at most [`MAX_EXPONENT`].
+//! Division by a value that turns out to be zero is an error, not a failed constraint.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    chip::{MyChip, MyConfig},
+    expr::{load_inputs, parse_field, EvalError, Expr},
+};
+
+/// The largest exponent `^` accepts. `x^n` lays out `x` once and takes up to
+/// `2 * log2(n)` multiplications.
+pub const MAX_EXPONENT: u32 = 64;
+
+/// A syntax or name-resolution error, located by 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Symbol(char),
+    Eof,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Number(digits) => write!(f, "`{}`", digits),
+            Token::Symbol(c) => write!(f, "`{}`", c),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// A token and the line and column it starts at.
+type Spanned = (Token, usize, usize);
+
+fn tokenize(src: &str) -> Result<Vec<Spanned>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = src.chars().peekable();
+    let (mut line, mut column) = (1, 1);
+
+    while let Some(&c) = chars.peek() {
+        let start = (line, column);
+        if c == '\n' {
+            chars.next();
+            line += 1;
+            column = 1;
+        } else if c.is_whitespace() {
+            chars.next();
+            column += 1;
+        } else if c == '/' {
+            chars.next();
+            if chars.peek() == Some(&'/') {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            } else {
+                column += 1;
+                tokens.push((Token::Symbol('/'), start.0, start.1));
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' || c.is_ascii_digit() {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+                column += 1;
+            }
+            let token = if word.chars().all(|c| c.is_ascii_digit()) {
+                Token::Number(word)
+            } else if word.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(ParseError {
+                    line: start.0,
+                    column: start.1,
+                    message: format!("invalid number `{}`", word),
+                });
+            } else {
+                Token::Ident(word)
+            };
+            tokens.push((token, start.0, start.1));
+        } else if "=;,+-*^()".contains(c) {
+            chars.next();
+            column += 1;
+            tokens.push((Token::Symbol(c), start.0, start.1));
+        } else {
+            return Err(ParseError {
+                line,
+                column,
+                message: format!("unexpected character `{}`", c),
+            });
+        }
+    }
+
+    tokens.push((Token::Eof, line, column));
+    Ok(tokens)
+}
+
+/// A parsed program: private inputs, assignments in order, and public outputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Program<F: FieldExt> {
+    pub inputs: Vec<String>,
+    pub assignments: Vec<(String, Expr<F>)>,
+    pub public: Vec<String>,
+}
+
+struct Parser<F: FieldExt> {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    program: Program<F>,
+}
+
+impl<F: FieldExt> Parser<F> {
+    fn peek(&self) -> &Spanned {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Spanned {
+        let token = self.tokens[self.pos].clone();
+        if token.0 != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error_at(&self, (_, line, column): &Spanned, message: String) -> ParseError {
+        ParseError {
+            line: *line,
+            column: *column,
+            message,
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ParseError> {
+        let token = self.next();
+        if token.0 == Token::Symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error_at(&token, format!("expected `{}`, found {}", symbol, token.0)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<(String, Spanned), ParseError> {
+        let token = self.next();
+        match &token.0 {
+            Token::Ident(name) if !is_keyword(name) => Ok((name.clone(), token)),
+            _ => Err(self.error_at(&token, format!("expected a name, found {}", token.0))),
+        }
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        self.program.inputs.iter().any(|input| input == name)
+            || self.program.assignments.iter().any(|(n, _)| n == name)
+    }
+
+    /// Parses a comma-separated list of names terminated by `;`.
+    fn names(&mut self) -> Result<Vec<(String, Spanned)>, ParseError> {
+        let mut names = vec![self.ident()?];
+        while self.peek().0 == Token::Symbol(',') {
+            self.next();
+            names.push(self.ident()?);
+        }
+        self.expect(';')?;
+        Ok(names)
+    }
+
+    fn statement(&mut self) -> Result<(), ParseError> {
+        let token = self.peek().clone();
+        match &token.0 {
+            Token::Ident(keyword) if keyword == "private" => {
+                self.next();
+                for (name, token) in self.names()? {
+                    if self.is_defined(&name) {
+                        return Err(self.error_at(&token, format!("`{}` is already defined", name)));
+                    }
+                    self.program.inputs.push(name);
+                }
+            }
+            Token::Ident(keyword) if keyword == "public" => {
+                self.next();
+                for (name, token) in self.names()? {
+                    if !self.is_defined(&name) {
+                        return Err(
+                            self.error_at(&token, format!("undeclared variable `{}`", name))
+                        );
+                    }
+                    self.program.public.push(name);
+                }
+            }
+            _ => {
+                let (name, token) = self.ident()?;
+                self.expect('=')?;
+                let expr = self.expr()?;
+                self.expect(';')?;
+                if self.is_defined(&name) {
+                    return Err(self.error_at(&token, format!("`{}` is already defined", name)));
+                }
+                self.program.assignments.push((name, expr));
+            }
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<Expr<F>, ParseError> {
+        let mut expr = self.term()?;
+        loop {
+            if self.peek().0 == Token::Symbol('+') {
+                self.next();
+                expr = expr + self.term()?;
+            } else if self.peek().0 == Token::Symbol('-') {
+                self.next();
+                expr = expr - self.term()?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr<F>, ParseError> {
+        let mut expr = self.unary()?;
+        loop {
+            if self.peek().0 == Token::Symbol('*') {
+                self.next();
+                expr = expr * self.unary()?;
+            } else if self.peek().0 == Token::Symbol('/') {
+                self.next();
+                expr = expr / self.unary()?;
+            } else {
+                break;
+            }
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr<F>, ParseError> {
+        if self.peek().0 == Token::Symbol('-') {
+            self.next();
+            return Ok(-self.unary()?);
+        }
+        self.factor()
+    }
+
+    fn factor(&mut self) -> Result<Expr<F>, ParseError> {
+        let base = self.atom()?;
+        if self.peek().0 != Token::Symbol('^') {
+            return Ok(base);
+        }
+        self.next();
+
+        let token = self.next();
+        let exponent = match &token.0 {
+            Token::Number(digits) => digits.parse::<u32>().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| self.error_at(&token, format!("expected an exponent, found {}", token.0)))?;
+        if exponent > MAX_EXPONENT {
+            return Err(self.error_at(
+                &token,
+                format!("exponent {} is larger than {}", token.0, MAX_EXPONENT),
+            ));
+        }
+
+        Ok(base.pow(exponent))
+    }
+
+    fn atom(&mut self) -> Result<Expr<F>, ParseError> {
+        let token = self.next();
+        match &token.0 {
+            Token::Number(digits) => parse_field(digits).map(Expr::constant).ok_or_else(|| {
+                self.error_at(
+                    &token,
+                    format!("{} is not below the field modulus", token.0),
+                )
+            }),
+            Token::Ident(name) if !is_keyword(name) => {
+                if self.is_defined(name) {
+                    Ok(Expr::var(name.clone()))
+                } else {
+                    Err(self.error_at(&token, format!("undeclared variable `{}`", name)))
+                }
+            }
+            Token::Symbol('(') => {
+                let expr = self.expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            _ => Err(self.error_at(&token, format!("expected an expression, found {}", token.0))),
+        }
+    }
+}
+
+fn is_keyword(name: &str) -> bool {
+    name == "private" || name == "public"
+}
+
+impl<F: FieldExt> Program<F> {
+    /// Parses `src`, rejecting syntax errors and references to undeclared variables.
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(src)?,
+            pos: 0,
+            program: Program {
+                inputs: vec![],
+                assignments: vec![],
+                public: vec![],
+            },
+        };
+        while parser.peek().0 != Token::Eof {
+            parser.statement()?;
+        }
+        Ok(parser.program)
+    }
+
+    /// Evaluates the program natively and returns its public values in instance order.
+    pub fn evaluate(&self, inputs: &BTreeMap<String, F>) -> Result<Vec<F>, EvalError> {
+        let mut vars = BTreeMap::new();
+        for name in &self.inputs {
+            let value = inputs
+                .get(name)
+                .ok_or_else(|| EvalError::UnknownVariable(name.clone()))?;
+            vars.insert(name.clone(), *value);
+        }
+        for (name, expr) in &self.assignments {
+            let value = expr.evaluate(&vars)?;
+            vars.insert(name.clone(), value);
+        }
+        self.public
+            .iter()
+            .map(|name| {
+                vars.get(name)
+                    .copied()
+                    .ok_or_else(|| EvalError::UnknownVariable(name.clone()))
+            })
+            .collect()
+    }
+
+    /// A circuit for this program whose inputs take the given values.
+    pub fn circuit(&self, inputs: &BTreeMap<String, F>) -> DslCircuit<F> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|name| {
+                let value = inputs
+                    .get(name)
+                    .copied()
+                    .map_or(Value::unknown(), Value::known);
+                (name.clone(), value)
+            })
+            .collect();
+        DslCircuit {
+            program: self.clone(),
+            inputs,
+        }
+    }
+}
+
+/// A circuit compiled from a [`Program`].
+#[derive(Clone, Debug)]
+pub struct DslCircuit<F: FieldExt> {
+    pub program: Program<F>,
+    pub inputs: BTreeMap<String, Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for DslCircuit<F> {
+    type Config = MyConfig;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.program.circuit(&BTreeMap::new())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        MyChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+
+        let mut vars = load_inputs(&chip, &mut layouter, &self.inputs)?;
+        for (name, expr) in &self.program.assignments {
+            let value = expr.assign(&chip, layouter.namespace(|| name.clone()), &vars)?;
+            vars.insert(name.clone(), value);
+        }
+
+        for (row, name) in self.program.public.iter().enumerate() {
+            let value = vars.get(name).cloned().ok_or(Error::Synthesis)?;
+            chip.expose_public(
+                layouter.namespace(|| format!("expose {}", name)),
+                value,
+                row,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,312 @@
+use std::io::{self, Write};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{Any, Circuit},
+};
+
+use crate::{
+    table::{InspectError, Table},
+    trace::{column_name, field, selector_names},
+};
+
+/// The assignment table of a circuit by row, as written to a spreadsheet.
+///
+/// The columns are `row`, then the fixed columns, the selectors, the advice and the
+/// instance columns, named as in a [`Trace`](crate::Trace), and last the regions and the
+/// cell annotations of the row. Unassigned cells, and cells whose value is unknown
+/// because the circuit has no witness, are empty. The table stops at the last row the
+/// circuit uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WitnessTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl WitnessTable {
+    /// Synthesizes `circuit` with `instance` into `2^k` rows and collects the table.
+    pub fn new<F: FieldExt, C: Circuit<F>>(
+        k: u32,
+        circuit: &C,
+        instance: &[Vec<F>],
+    ) -> Result<Self, InspectError> {
+        let table = Table::record(k, circuit, instance)?;
+        let shape = &table.shape;
+
+        let mut headers = vec!["row".to_string()];
+        headers.extend((0..shape.fixed_columns).map(|i| column_name((Any::Fixed, i))));
+        headers.extend(selector_names(&table));
+        headers.extend((0..shape.advice_columns).map(|i| column_name((Any::Advice, i))));
+        headers.extend((0..shape.instance_columns).map(|i| column_name((Any::Instance, i))));
+        headers.push("region".to_string());
+        headers.push("annotations".to_string());
+
+        let mut annotations = vec![vec![]; table.rows_used];
+        let cells = table
+            .regions
+            .iter()
+            .flat_map(|region| &region.cells)
+            .chain(&table.constants);
+        for cell in cells {
+            annotations[cell.row].push(format!(
+                "{}: {}",
+                column_name(cell.column),
+                cell.annotation
+            ));
+        }
+        let mut regions = vec![vec![]; table.rows_used];
+        for region in &table.regions {
+            if let Some((start, end)) = region.rows {
+                let mut name = region.namespace.clone();
+                name.push(region.name.clone());
+                for row in &mut regions[start..=end] {
+                    row.push(name.join(" / "));
+                }
+            }
+        }
+
+        let value = |value: &Option<F>| value.map(field).unwrap_or_default();
+        let rows = (0..table.rows_used)
+            .map(|row| {
+                let mut cells = vec![row.to_string()];
+                cells.extend(table.fixed.iter().map(|column| value(&column[row])));
+                cells.extend(
+                    table
+                        .selectors
+                        .iter()
+                        .map(|column| (column[row] as u8).to_string()),
+                );
+                cells.extend(table.advice.iter().map(|column| value(&column[row])));
+                cells.extend(table.instance.iter().map(|column| field(column[row])));
+                cells.push(regions[row].join("; "));
+                cells.push(annotations[row].join("; "));
+                cells
+            })
+            .collect();
+
+        Ok(WitnessTable { headers, rows })
+    }
+
+    /// Writes the table as CSV, with a header line.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let line: Vec<_> = row.iter().map(|cell| csv_field(cell)).collect();
+            writeln!(writer, "{}", line.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the table as an XLSX workbook with a single sheet.
+    ///
+    /// The workbook has no timestamps, so the same table always gives the same bytes.
+    #[cfg(feature = "xlsx")]
+    pub fn write_xlsx<W: Write>(&self, writer: W) -> io::Result<()> {
+        xlsx::write(writer, &self.headers, &self.rows)
+    }
+}
+
+/// Quotes a CSV field if it needs it.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// A minimal XLSX writer: the few XML parts a spreadsheet needs, in an uncompressed zip.
+#[cfg(feature = "xlsx")]
+mod xlsx {
+    use std::io::{self, Write};
+
+    const CONTENT_TYPES: &str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" "#,
+        r#"ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Override PartName="/xl/workbook.xml" ContentType="application/"#,
+        r#"vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/"#,
+        r#"vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+        r#"</Types>"#,
+    );
+
+    const RELS: &str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" "#,
+        r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/"#,
+        r#"officeDocument" Target="xl/workbook.xml"/>"#,
+        r#"</Relationships>"#,
+    );
+
+    const WORKBOOK: &str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+        r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+        r#"<sheets><sheet name="witness" sheetId="1" r:id="rId1"/></sheets>"#,
+        r#"</workbook>"#,
+    );
+
+    const WORKBOOK_RELS: &str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" "#,
+        r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/"#,
+        r#"worksheet" Target="worksheets/sheet1.xml"/>"#,
+        r#"</Relationships>"#,
+    );
+
+    const SHEET_START: &str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+        r#"<sheetViews><sheetView workbookViewId="0">"#,
+        r#"<pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/>"#,
+        r#"</sheetView></sheetViews><sheetData>"#,
+    );
+
+    pub(super) fn write<W: Write>(
+        writer: W,
+        headers: &[String],
+        rows: &[Vec<String>],
+    ) -> io::Result<()> {
+        let sheet = sheet(headers, rows);
+        let mut zip = Zip::new(writer);
+        zip.file("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+        zip.file("_rels/.rels", RELS.as_bytes())?;
+        zip.file("xl/workbook.xml", WORKBOOK.as_bytes())?;
+        zip.file("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes())?;
+        zip.file("xl/worksheets/sheet1.xml", sheet.as_bytes())?;
+        zip.finish()
+    }
+
+    /// The worksheet, with the header row frozen.
+    fn sheet(headers: &[String], rows: &[Vec<String>]) -> String {
+        let mut xml = String::from(SHEET_START);
+        for (i, row) in std::iter::once(headers)
+            .chain(rows.iter().map(Vec::as_slice))
+            .enumerate()
+        {
+            xml.push_str(&format!("<row r=\"{}\">", i + 1));
+            for (j, cell) in row.iter().enumerate() {
+                if cell.is_empty() {
+                    continue;
+                }
+                let reference = format!("{}{}", column_letters(j), i + 1);
+                // Spreadsheets keep 15 significant digits, so longer numbers stay text.
+                if cell.len() <= 15 && cell.parse::<i64>().is_ok() {
+                    xml.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", reference, cell));
+                } else {
+                    xml.push_str(&format!(
+                        "<c r=\"{}\" t=\"inlineStr\"><is><t>{}</t></is></c>",
+                        reference,
+                        escape(cell)
+                    ));
+                }
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>");
+        xml
+    }
+
+    /// `A`, `B`, ..., `Z`, `AA`, ... for the zero-based column `index`.
+    fn column_letters(mut index: usize) -> String {
+        let mut letters = vec![];
+        loop {
+            letters.push(b'A' + (index % 26) as u8);
+            if index < 26 {
+                break;
+            }
+            index = index / 26 - 1;
+        }
+        letters.reverse();
+        String::from_utf8(letters).unwrap()
+    }
+
+    fn escape(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    /// A zip archive of stored (uncompressed) files, dated 1980-01-01.
+    struct Zip<W> {
+        writer: W,
+        offset: u32,
+        directory: Vec<u8>,
+        entries: u16,
+    }
+
+    impl<W: Write> Zip<W> {
+        const DATE: u16 = (1 << 5) | 1;
+
+        fn new(writer: W) -> Self {
+            Zip {
+                writer,
+                offset: 0,
+                directory: vec![],
+                entries: 0,
+            }
+        }
+
+        fn file(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+            let crc = crc32fast::hash(data);
+            let size = data.len() as u32;
+
+            let mut header = vec![];
+            header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+            header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            header.extend_from_slice(&0u16.to_le_bytes()); // flags
+            header.extend_from_slice(&0u16.to_le_bytes()); // stored
+            header.extend_from_slice(&0u16.to_le_bytes()); // time
+            header.extend_from_slice(&Self::DATE.to_le_bytes());
+            header.extend_from_slice(&crc.to_le_bytes());
+            header.extend_from_slice(&size.to_le_bytes());
+            header.extend_from_slice(&size.to_le_bytes());
+            header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+            header.extend_from_slice(name.as_bytes());
+
+            let directory = &mut self.directory;
+            directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            directory.extend_from_slice(&header[4..30]);
+            directory.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            directory.extend_from_slice(&0u16.to_le_bytes()); // disk
+            directory.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+            directory.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+            directory.extend_from_slice(&self.offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+
+            self.writer.write_all(&header)?;
+            self.writer.write_all(data)?;
+            self.offset += header.len() as u32 + size;
+            self.entries += 1;
+            Ok(())
+        }
+
+        fn finish(mut self) -> io::Result<()> {
+            let mut end = vec![];
+            end.extend_from_slice(&0x06054b50u32.to_le_bytes());
+            end.extend_from_slice(&0u16.to_le_bytes()); // disk
+            end.extend_from_slice(&0u16.to_le_bytes()); // disk with the directory
+            end.extend_from_slice(&self.entries.to_le_bytes());
+            end.extend_from_slice(&self.entries.to_le_bytes());
+            end.extend_from_slice(&(self.directory.len() as u32).to_le_bytes());
+            end.extend_from_slice(&self.offset.to_le_bytes());
+            end.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+            self.writer.write_all(&self.directory)?;
+            self.writer.write_all(&end)?;
+            self.writer.flush()
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use std::{collections::BTreeMap, fmt, ops};
+
+use crate::chip::{MyChip, MyConfig, Number};
+
+/// An arithmetic expression over named private inputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr<F: FieldExt> {
+    Var(String),
+    Const(F),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+    Div(Box<Expr<F>>, Box<Expr<F>>),
+    Neg(Box<Expr<F>>),
+    /// `base^n`, with the base laid out once.
+    Pow(Box<Expr<F>>, u32),
+}
+
+/// The reasons an expression cannot be evaluated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    /// The named variable has no value.
+    UnknownVariable(String),
+    /// A divisor evaluated to zero.
+    DivisionByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "no value for `{}`", name),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl<F: FieldExt> Expr<F> {
+    pub fn var(name: impl Into<String>) -> Self {
+        Expr::Var(name.into())
+    }
+
+    pub fn constant(value: F) -> Self {
+        Expr::Const(value)
+    }
+
+    pub fn pow(self, n: u32) -> Self {
+        Expr::Pow(Box::new(self), n)
+    }
+
+    /// The names of the variables in this expression, in sorted order.
+    pub fn variables(&self) -> Vec<&str> {
+        fn collect<'a, F: FieldExt>(expr: &'a Expr<F>, vars: &mut Vec<&'a str>) {
+            match expr {
+                Expr::Var(name) => vars.push(name),
+                Expr::Const(_) => {}
+                Expr::Add(lhs, rhs)
+                | Expr::Sub(lhs, rhs)
+                | Expr::Mul(lhs, rhs)
+                | Expr::Div(lhs, rhs) => {
+                    collect(lhs, vars);
+                    collect(rhs, vars);
+                }
+                Expr::Neg(inner) | Expr::Pow(inner, _) => collect(inner, vars),
+            }
+        }
+
+        let mut vars = vec![];
+        collect(self, &mut vars);
+        vars.sort_unstable();
+        vars.dedup();
+        vars
+    }
+
+    /// Evaluates the expression natively.
+    pub fn evaluate(&self, vars: &BTreeMap<String, F>) -> Result<F, EvalError> {
+        match self {
+            Expr::Var(name) => vars
+                .get(name)
+                .copied()
+                .ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Const(value) => Ok(*value),
+            Expr::Add(lhs, rhs) => Ok(lhs.evaluate(vars)? + rhs.evaluate(vars)?),
+            Expr::Sub(lhs, rhs) => Ok(lhs.evaluate(vars)? - rhs.evaluate(vars)?),
+            Expr::Mul(lhs, rhs) => Ok(lhs.evaluate(vars)? * rhs.evaluate(vars)?),
+            Expr::Div(lhs, rhs) => {
+                let lhs = lhs.evaluate(vars)?;
+                let rhs: Option<F> = rhs.evaluate(vars)?.invert().into();
+                Ok(lhs * rhs.ok_or(EvalError::DivisionByZero)?)
+            }
+            Expr::Neg(inner) => Ok(-inner.evaluate(vars)?),
+            Expr::Pow(base, n) => Ok(base.evaluate(vars)?.pow_vartime([*n as u64])),
+        }
+    }
+
+    /// Lays out the expression with `chip`, reading variables from `vars`.
+    ///
+    /// Fails with `Error::Synthesis` if a divisor is known to be zero.
+    pub fn assign(
+        &self,
+        chip: &MyChip<F>,
+        mut layouter: impl Layouter<F>,
+        vars: &BTreeMap<String, Number<F>>,
+    ) -> Result<Number<F>, Error> {
+        match self {
+            Expr::Var(name) => vars.get(name).cloned().ok_or(Error::Synthesis),
+            Expr::Const(value) => {
+                chip.load_constant(layouter.namespace(|| format!("load {}", self)), *value)
+            }
+            Expr::Add(lhs, rhs) => {
+                let lhs = lhs.assign(chip, layouter.namespace(|| "lhs"), vars)?;
+                let rhs = rhs.assign(chip, layouter.namespace(|| "rhs"), vars)?;
+                chip.add(layouter.namespace(|| self.to_string()), lhs, rhs)
+            }
+            Expr::Sub(lhs, rhs) => {
+                let lhs = lhs.assign(chip, layouter.namespace(|| "lhs"), vars)?;
+                let rhs = rhs.assign(chip, layouter.namespace(|| "rhs"), vars)?;
+                chip.sub(layouter.namespace(|| self.to_string()), lhs, rhs)
+            }
+            Expr::Mul(lhs, rhs) => {
+                let lhs = lhs.assign(chip, layouter.namespace(|| "lhs"), vars)?;
+                let rhs = rhs.assign(chip, layouter.namespace(|| "rhs"), vars)?;
+                chip.mul(layouter.namespace(|| self.to_string()), lhs, rhs)
+            }
+            Expr::Div(lhs, rhs) => {
+                let lhs = lhs.assign(chip, layouter.namespace(|| "lhs"), vars)?;
+                let rhs = rhs.assign(chip, layouter.namespace(|| "rhs"), vars)?;
+                chip.div(layouter.namespace(|| self.to_string()), lhs, rhs)
+            }
+            Expr::Neg(inner) => {
+                let inner = inner.assign(chip, layouter.namespace(|| "input"), vars)?;
+                chip.neg(layouter.namespace(|| self.to_string()), inner)
+            }
+            Expr::Pow(_, 0) => {
+                chip.load_constant(layouter.namespace(|| format!("load {}", self)), F::one())
+            }
+            Expr::Pow(base, n) => {
+                let base = base.assign(chip, layouter.namespace(|| "base"), vars)?;
+                // Square and multiply, from the highest bit of `n` down.
+                let mut acc = base.clone();
+                for bit in (0..31 - n.leading_zeros()).rev() {
+                    acc = chip.mul(layouter.namespace(|| "square"), acc.clone(), acc)?;
+                    if (n >> bit) & 1 == 1 {
+                        acc = chip.mul(layouter.namespace(|| "multiply"), acc, base.clone())?;
+                    }
+                }
+                Ok(acc)
+            }
+        }
+    }
+}
+
+impl<F: FieldExt> ops::Add for Expr<F> {
+    type Output = Expr<F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Expr::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: FieldExt> ops::Sub for Expr<F> {
+    type Output = Expr<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Expr::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: FieldExt> ops::Mul for Expr<F> {
+    type Output = Expr<F>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Expr::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: FieldExt> ops::Div for Expr<F> {
+    type Output = Expr<F>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Expr::Div(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: FieldExt> ops::Neg for Expr<F> {
+    type Output = Expr<F>;
+
+    fn neg(self) -> Self::Output {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+/// Writes small field elements in decimal and everything else in hex.
+pub(crate) fn fmt_field<F: FieldExt>(value: &F, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match small(value) {
+        Some(n) => write!(f, "{}", n),
+        None => write!(f, "{:?}", value),
+    }
+}
+
+/// The value of a field element, if it fits in a `u64`.
+pub(crate) fn small<F: FieldExt>(value: &F) -> Option<u64> {
+    let repr = value.to_repr();
+    let bytes = repr.as_ref();
+    if bytes[8..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[..8]);
+    Some(u64::from_le_bytes(low))
+}
+
+/// Parses a field element written in decimal or as 0x-prefixed big-endian hex. Values
+/// that are not below the modulus are rejected in either form.
+pub fn parse_field<F: FieldExt>(s: &str) -> Option<F> {
+    let mut repr = F::Repr::default();
+    let bytes = repr.as_mut();
+    if let Some(hex) = s.strip_prefix("0x") {
+        if hex.is_empty() || hex.len() > 2 * bytes.len() {
+            return None;
+        }
+        for (i, digit) in hex.chars().rev().enumerate() {
+            bytes[i / 2] |= (digit.to_digit(16)? as u8) << (4 * (i % 2));
+        }
+    } else {
+        if s.is_empty() {
+            return None;
+        }
+        for digit in s.chars() {
+            // The little-endian bytes times ten, plus the digit.
+            let mut carry = digit.to_digit(10)?;
+            for byte in bytes.iter_mut() {
+                let n = *byte as u32 * 10 + carry;
+                *byte = n as u8;
+                carry = n >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+    }
+    F::from_repr(repr).into()
+}
+
+impl<F: FieldExt> fmt::Display for Expr<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Const(value) => fmt_field(value, f),
+            Expr::Add(lhs, rhs) => write!(f, "{} + {}", lhs, rhs),
+            Expr::Sub(lhs, rhs) => match rhs.as_ref() {
+                Expr::Add(..) | Expr::Sub(..) => write!(f, "{} - ({})", lhs, rhs),
+                _ => write!(f, "{} - {}", lhs, rhs),
+            },
+            Expr::Mul(lhs, rhs) | Expr::Div(lhs, rhs) => {
+                let op = if matches!(self, Expr::Mul(..)) {
+                    "*"
+                } else {
+                    "/"
+                };
+                match lhs.as_ref() {
+                    Expr::Add(..) | Expr::Sub(..) => write!(f, "({}) {} ", lhs, op)?,
+                    _ => write!(f, "{} {} ", lhs, op)?,
+                }
+                match (self, rhs.as_ref()) {
+                    (_, Expr::Add(..) | Expr::Sub(..))
+                    | (Expr::Div(..), Expr::Mul(..) | Expr::Div(..)) => {
+                        write!(f, "({})", rhs)
+                    }
+                    _ => write!(f, "{}", rhs),
+                }
+            }
+            Expr::Neg(inner) => match inner.as_ref() {
+                Expr::Var(_) | Expr::Const(_) => write!(f, "-{}", inner),
+                _ => write!(f, "-({})", inner),
+            },
+            Expr::Pow(base, n) => match base.as_ref() {
+                Expr::Var(_) | Expr::Const(_) => write!(f, "{}^{}", base, n),
+                _ => write!(f, "({})^{}", base, n),
+            },
+        }
+    }
+}
+
+/// A circuit proving that `expr`, evaluated over private `inputs`, equals the public
+/// instance at row 0.
+#[derive(Clone, Debug)]
+pub struct ExprCircuit<F: FieldExt> {
+    pub expr: Expr<F>,
+    pub inputs: BTreeMap<String, Value<F>>,
+}
+
+impl<F: FieldExt> ExprCircuit<F> {
+    /// A circuit for `expr` whose variables take the given values.
+    pub fn new(expr: Expr<F>, inputs: &BTreeMap<String, F>) -> Self {
+        let inputs = expr
+            .variables()
+            .into_iter()
+            .map(|name| {
+                let value = inputs
+                    .get(name)
+                    .copied()
+                    .map_or(Value::unknown(), Value::known);
+                (name.to_string(), value)
+            })
+            .collect();
+        ExprCircuit { expr, inputs }
+    }
+
+    /// A circuit for `expr` without a witness, for key generation.
+    pub fn empty(expr: Expr<F>) -> Self {
+        Self::new(expr, &BTreeMap::new())
+    }
+}
+
+/// Loads each of `inputs` once as a private value.
+pub(crate) fn load_inputs<F: FieldExt>(
+    chip: &MyChip<F>,
+    layouter: &mut impl Layouter<F>,
+    inputs: &BTreeMap<String, Value<F>>,
+) -> Result<BTreeMap<String, Number<F>>, Error> {
+    inputs
+        .iter()
+        .map(|(name, value)| {
+            let number =
+                chip.load_private(layouter.namespace(|| format!("load {}", name)), *value)?;
+            Ok((name.clone(), number))
+        })
+        .collect()
+}
+
+impl<F: FieldExt> Circuit<F> for ExprCircuit<F> {
+    type Config = MyConfig;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::empty(self.expr.clone())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        MyChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+
+        let vars = load_inputs(&chip, &mut layouter, &self.inputs)?;
+        let out = self
+            .expr
+            .assign(&chip, layouter.namespace(|| self.expr.to_string()), &vars)?;
+
+        chip.expose_public(layouter.namespace(|| "expose output"), out, 0)
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// The instance column of a circuit as a typed struct, one named field per row.
+pub trait PublicInputs<F>: Sized {
+    /// The names of the public inputs and outputs, in row order.
+    const NAMES: &'static [&'static str];
+
+    /// The values in row order.
+    fn to_instance(&self) -> Vec<F>;
+
+    /// Reads the values back from an instance column with one row per name.
+    fn from_instance(instance: &[F]) -> Result<Self, InstanceError>;
+
+    /// The instance row holding `name`.
+    fn row(name: &str) -> Result<usize, InstanceError> {
+        Self::NAMES
+            .iter()
+            .position(|n| *n == name)
+            .ok_or_else(|| InstanceError::UnknownName {
+                name: name.to_string(),
+                expected: Self::NAMES,
+            })
+    }
+
+    /// Checks that `instance` has exactly one row per name.
+    fn check(instance: &[F]) -> Result<(), InstanceError> {
+        if instance.len() == Self::NAMES.len() {
+            Ok(())
+        } else {
+            Err(InstanceError::Length {
+                expected: Self::NAMES,
+                found: instance.len(),
+            })
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InstanceError {
+    /// The instance column does not have one row per public input.
+    Length {
+        expected: &'static [&'static str],
+        found: usize,
+    },
+    /// `name` is not one of the public inputs.
+    UnknownName {
+        name: String,
+        expected: &'static [&'static str],
+    },
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstanceError::Length { expected, found } => write!(
+                f,
+                "expected {} public inputs ({}), found {}",
+                expected.len(),
+                expected.join(", "),
+                found
+            ),
+            InstanceError::UnknownName { name, expected } => write!(
+                f,
+                "unknown public input `{}`, expected one of {}",
+                name,
+                expected.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InstanceError {}
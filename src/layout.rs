@@ -0,0 +1,183 @@
+use std::{fmt, ops::Range, path::Path};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    dev::{circuit_dot_graph, CircuitLayout},
+    plonk::{Circuit, ConstraintSystem},
+};
+use plotters::{
+    coord::Shift,
+    drawing::{DrawingArea, DrawingAreaErrorKind, IntoDrawingArea},
+    prelude::{BitMapBackend, DrawingBackend, SVGBackend, WHITE},
+};
+
+use crate::{shape::Shape, table::Table};
+
+/// The image format of a rendered layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutFormat {
+    Png,
+    Svg,
+}
+
+impl LayoutFormat {
+    /// The format for the extension of `path`, `.png` or `.svg`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "png" => Some(LayoutFormat::Png),
+            "svg" => Some(LayoutFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// How to draw a circuit layout with [`CircuitLayout`].
+///
+/// Columns are drawn in the order instance, advice, fixed, with the selectors last
+/// among the fixed columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutOptions {
+    /// The image size in pixels.
+    pub width: u32,
+    pub height: u32,
+    /// A title above the layout, if any.
+    pub title: Option<String>,
+    /// The columns to draw; all of them if `None`.
+    pub view_width: Option<Range<usize>>,
+    /// The rows to draw; all `2^k` rows if `None`.
+    pub view_height: Option<Range<usize>>,
+    /// Whether to write the region names on the regions.
+    pub show_labels: bool,
+    /// Whether to mark the cells in equality constraints, in red.
+    pub mark_equality_cells: bool,
+    /// Whether to draw the equality constraints as lines between cells.
+    pub show_equality_constraints: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        LayoutOptions {
+            width: 1024,
+            height: 768,
+            title: None,
+            view_width: None,
+            view_height: None,
+            show_labels: true,
+            mark_equality_cells: false,
+            show_equality_constraints: false,
+        }
+    }
+}
+
+impl LayoutOptions {
+    fn circuit_layout(&self) -> CircuitLayout {
+        let mut layout = CircuitLayout::default()
+            .show_labels(self.show_labels)
+            .mark_equality_cells(self.mark_equality_cells)
+            .show_equality_constraints(self.show_equality_constraints);
+        if let Some(columns) = &self.view_width {
+            layout = layout.view_width(columns.clone());
+        }
+        if let Some(rows) = &self.view_height {
+            layout = layout.view_height(rows.clone());
+        }
+        layout
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The output path does not end in `.png` or `.svg`.
+    UnknownFormat,
+    /// The circuit could not be synthesized, for example because it does not fit in
+    /// `2^k` rows.
+    Synthesis(String),
+    /// The drawing backend failed, for example to write the image.
+    Drawing(String),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::UnknownFormat => write!(f, "layout images must be .png or .svg"),
+            LayoutError::Synthesis(e) => write!(f, "cannot lay out the circuit: {}", e),
+            LayoutError::Drawing(e) => write!(f, "cannot draw the layout: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl<E: std::error::Error + Send + Sync> From<DrawingAreaErrorKind<E>> for LayoutError {
+    fn from(e: DrawingAreaErrorKind<E>) -> Self {
+        LayoutError::Drawing(e.to_string())
+    }
+}
+
+/// Draws the layout of `circuit` at `k` to `path`, as PNG or SVG by its extension.
+///
+/// The layout only depends on the regions the circuit assigns, so a circuit without a
+/// witness will do.
+pub fn render_layout<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+    options: &LayoutOptions,
+    path: &Path,
+) -> Result<(), LayoutError> {
+    let size = (options.width, options.height);
+    match LayoutFormat::from_path(path).ok_or(LayoutError::UnknownFormat)? {
+        LayoutFormat::Png => draw(
+            k,
+            circuit,
+            options,
+            BitMapBackend::new(path, size).into_drawing_area(),
+        ),
+        LayoutFormat::Svg => draw(
+            k,
+            circuit,
+            options,
+            SVGBackend::new(path, size).into_drawing_area(),
+        ),
+    }
+}
+
+/// Draws the layout of `circuit` at `k` as an SVG document.
+pub fn layout_svg<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+    options: &LayoutOptions,
+) -> Result<String, LayoutError> {
+    let mut svg = String::new();
+    let area = SVGBackend::with_string(&mut svg, (options.width, options.height));
+    draw(k, circuit, options, area.into_drawing_area())?;
+    Ok(svg)
+}
+
+/// The namespaces and regions of `circuit` as a Graphviz DOT graph.
+pub fn layout_dot<F: FieldExt, C: Circuit<F>>(circuit: &C) -> String {
+    circuit_dot_graph(circuit)
+}
+
+fn draw<F: FieldExt, C: Circuit<F>, DB: DrawingBackend>(
+    k: u32,
+    circuit: &C,
+    options: &LayoutOptions,
+    area: DrawingArea<DB, Shift>,
+) -> Result<(), LayoutError> {
+    // `CircuitLayout::render` panics if the circuit does not synthesize, so record it
+    // first. The instance values do not matter to the layout.
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+    let shape = Shape::new::<F, C>(&cs).map_err(|e| LayoutError::Synthesis(e.to_string()))?;
+    let instance = vec![vec![]; shape.instance_columns];
+    Table::record(k, circuit, &instance).map_err(|e| LayoutError::Synthesis(e.to_string()))?;
+
+    area.fill(&WHITE)?;
+    let area = match &options.title {
+        Some(title) => area.titled(title, ("sans-serif", 60))?,
+        None => area,
+    };
+    options.circuit_layout().render(k, circuit, &area)?;
+    area.present()?;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Halo2 simple example:
+//!
+//! ```text
+//! 2 * (a ^ 2 + b * c) = d
+//! poseidon(a, b, c) = H
+//! ```
+
+mod chip;
+mod circuit;
+mod dsl;
+mod export;
+mod expr;
+mod instance;
+#[cfg(feature = "layout")]
+mod layout;
+mod merkle;
+mod min_k;
+mod params;
+mod poseidon;
+mod proof;
+mod proof_file;
+mod shape;
+mod stats;
+mod table;
+mod trace;
+mod vk;
+
+pub use chip::{MyChip, MyConfig, Number, RangeConfig, RANGE_TABLE_BITS};
+pub use circuit::{ConstantMode, MyCircuit, MyCircuitConfig, MyPublicInputs};
+pub use dsl::{DslCircuit, ParseError, Program, MAX_EXPONENT};
+pub use export::WitnessTable;
+pub use expr::{parse_field, EvalError, Expr, ExprCircuit};
+pub use instance::{InstanceError, PublicInputs};
+#[cfg(feature = "layout")]
+pub use layout::{layout_dot, layout_svg, render_layout, LayoutError, LayoutFormat, LayoutOptions};
+pub use merkle::{
+    assign_merkle_root, merkle_parent, merkle_root, MerkleCircuit, MerkleTree, PathStep,
+};
+pub use min_k::{min_k, MAX_K};
+pub use params::{load_params, read_params, save_params, write_params, ParamsError};
+pub use poseidon::{poseidon_hash, poseidon_permute, PoseidonChip, PoseidonConfig};
+pub use proof::{
+    keygen, prove, prove_many, setup, verify, verify_batch, verify_many, Proof, ProveError,
+    VerifyError,
+};
+pub use proof_file::{ProofFile, ProofFileError, TranscriptKind, PROOF_FILE_VERSION};
+pub use stats::CircuitStats;
+pub use table::InspectError;
+pub use trace::{Trace, TraceCell, TraceGate, TraceRegion};
+pub use vk::{circuit_fingerprint, VkArtifact, VkError};
@@ -1,319 +1,634 @@
+#[cfg(feature = "layout")]
+use halo2_hi::{layout_dot, render_layout, LayoutOptions};
+use halo2_hi::{
+    load_params, min_k, prove, save_params, setup, verify, CircuitStats, ConstantMode, MyCircuit,
+    MyPublicInputs, Program, ProofFile, PublicInputs, Trace, TranscriptKind, VkArtifact,
+    WitnessTable,
+};
 use halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
-    poly::Rotation,
+    circuit::Value,
+    dev::MockProver,
+    pasta::{EqAffine, Fp},
+    plonk::{keygen_pk, VerifyingKey},
+    poly::commitment::Params,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs, io, process,
+    time::Instant,
 };
-use halo2_proofs::{dev::MockProver, pasta::Fp};
-use std::marker::PhantomData;
-// use plotters::prelude::*;
+#[cfg(feature = "layout")]
+use std::{ops::Range, path::Path};
+
+const USAGE: &str = "\
+Usage:
+    halo2_hi setup  [--k <k>] [--mode <fixed|public>] [--constant <n>] [--params <file>] [--vk <file>]
+    halo2_hi prove  --witness <file> [--constant <n>] [--params <file>] [--vk <file>] [--proof <file>]
+    halo2_hi verify --public <d>|<name=value,...> [--params <file>] [--vk <file>] [--proof <file>]
+    halo2_hi check  --circuit <file.circ> --witness <file> [--k <k>]
+    halo2_hi stats  [--k <k>] [--mode <fixed|public>] [--constant <n>]
+    halo2_hi trace  [--witness <file>] [--k <k>] [--mode <fixed|public>] [--constant <n>]
+                    [--format <text|json>] [--output <file>]
+    halo2_hi export [--witness <file>] [--k <k>] [--mode <fixed|public>] [--constant <n>]
+                    [--output <file.csv|file.xlsx>]
+    halo2_hi layout [--k <k>] [--mode <fixed|public>] [--constant <n>] [--output <file.png|file.svg>]
+                    [--dot <file>] [--width <px>] [--height <px>] [--columns <a..b>]
+                    [--rows <a..b>] [--labels <true|false>] [--title <text>]
+    halo2_hi demo
+
+The witness file holds one `name = value` pair per line, for `a`, `b` and `c` or
+for the private inputs of the `.circ` program.
+`--k` defaults to the smallest `k` that fits the circuit.
+With `--mode fixed` the constant is part of the key; with `--mode public` it is a
+public input chosen at proving time, and one key works for every constant.
+The public inputs of `MyCircuit` are `d`, `H` and `constant`; `--public` gives the
+expected values and must include `d`, and `constant` for a key made with `--mode public`.
+Public inputs not given are taken from the proof file unchecked.
+`stats` reports the size and cost of `MyCircuit`, and `trace` prints its gates, regions
+and cells, with the values if a witness is given. `export` writes the assignment table
+by row; XLSX output needs the `xlsx` feature. `layout` draws the regions of
+`MyCircuit` and needs the `layout` feature.
+Field elements are written in decimal or as 0x-prefixed big-endian hex.";
+
+const DEFAULT_MODE: &str = "fixed";
+const DEFAULT_CONSTANT: &str = "2";
+const DEFAULT_PARAMS: &str = "params.bin";
+const DEFAULT_VK: &str = "vk.bin";
+const DEFAULT_PROOF: &str = "proof.bin";
+#[cfg(feature = "layout")]
+const DEFAULT_LAYOUT: &str = "layout.png";
+
+/// A subcommand, run with its options.
+type Command = fn(&Options) -> Result<(), String>;
+
+/// `--name value` options following a subcommand.
+struct Options(HashMap<String, String>);
+
+impl Options {
+    /// Parses `args`, rejecting options other than `allowed` so that a misspelt option
+    /// is not silently replaced by its default.
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Self, String> {
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument `{}`", arg))?;
+            if !allowed.contains(&name) {
+                return Err(format!("unknown option `--{}`", name));
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `--{}`", name))?;
+            options.insert(name.to_string(), value.clone());
+        }
+        Ok(Options(options))
+    }
 
-// const * a^2 + b * c = d
-// a * a + b * c = d
+    fn get(&self, name: &str) -> Result<&str, String> {
+        self.get_optional(name)
+            .ok_or_else(|| format!("missing required option `--{}`", name))
+    }
 
-// advice, fixed(selector), instance column
+    fn get_optional(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
 
-#[derive(Clone, Debug)]
-struct MyConfig {
-    advice: [Column<Advice>; 2],
-    instance: Column<Instance>,
-    s_mul: Selector,
-    s_add: Selector,
+    fn get_or<'a>(&'a self, name: &str, default: &'a str) -> &'a str {
+        self.get_optional(name).unwrap_or(default)
+    }
 }
 
-struct MyChip<F: FieldExt> {
-    config: MyConfig,
-    _marker: PhantomData<F>,
+/// Parses a field element written in decimal or as 0x-prefixed big-endian hex.
+fn parse_field(s: &str) -> Result<Fp, String> {
+    let s = s.trim();
+    halo2_hi::parse_field(s).ok_or_else(|| format!("invalid field element `{}`", s))
 }
 
-impl<F: FieldExt> MyChip<F> {
-    fn new(config: MyConfig) -> Self {
-        MyChip {
-            config,
-            _marker: PhantomData,
-        }
+/// Parses `--public`: either a bare value for `d` or comma-separated `name=value` pairs.
+fn parse_public(s: &str) -> Result<Vec<(String, Fp)>, String> {
+    if !s.contains('=') {
+        return Ok(vec![("d".to_string(), parse_field(s)?)]);
     }
+    s.split(',')
+        .map(|pair| {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected `name=value` in `--public`, found `{}`", pair))?;
+            Ok((name.trim().to_string(), parse_field(value)?))
+        })
+        .collect()
 }
 
-#[derive(Clone)]
-struct Number<F: FieldExt>(AssignedCell<F, F>);
-
-impl<F: FieldExt> MyChip<F> {
-    fn load_private(
-        &self,
-        mut layouter: impl Layouter<F>,
-        value: Value<F>,
-    ) -> Result<Number<F>, Error> {
-        layouter.assign_region(
-            || "load private",
-            |mut region| {
-                region
-                    .assign_advice(
-                        || "private input",
-                        self.config.advice[0],
-                        0,
-                        || value,
-                    )
-                    .map(Number)
-            },
-        )
+/// Parses `--k`, or falls back to the smallest `k` that fits the circuit.
+fn parse_k(
+    options: &Options,
+    min_k: impl FnOnce() -> Result<u32, halo2_proofs::plonk::Error>,
+) -> Result<u32, String> {
+    match options.get_optional("k") {
+        Some(k) => k
+            .parse()
+            .map_err(|_| "`--k` must be a positive integer".to_string()),
+        None => min_k().map_err(|e| format!("cannot find a k that fits the circuit: {}", e)),
     }
+}
 
-    fn load_constant(
-        &self,
-        mut layouter: impl Layouter<F>,
-        constant: F,
-    ) -> Result<Number<F>, Error> {
-        layouter.assign_region(
-            || "load constant",
-            |mut region| {
-                region
-                    .assign_advice_from_constant(
-                        || "constant value",
-                        self.config.advice[0],
-                        0,
-                        constant,
-                    )
-                    .map(Number)
-            },
-        )
+fn parse_mode(s: &str) -> Result<ConstantMode, String> {
+    match s {
+        "fixed" => Ok(ConstantMode::Fixed),
+        "public" => Ok(ConstantMode::Public),
+        _ => Err(format!(
+            "`--mode` must be `fixed` or `public`, found `{}`",
+            s
+        )),
     }
+}
 
-    fn mul(
-        &self,
-        mut layouter: impl Layouter<F>,
-        a: Number<F>,
-        b: Number<F>,
-    ) -> Result<Number<F>, Error> {
-        layouter.assign_region(
-            || "mul",
-            |mut region| {
-                self.config.s_mul.enable(&mut region, 0)?;
-
-                // copy cell value to region's advice cell and constrains them to be equal.
-                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
-                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
-
-                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a * *b));
-                region
-                    .assign_advice(
-                        || "lhs * rhs",
-                        self.config.advice[0],
-                        1,
-                        || value,
-                    )
-                    .map(Number)
-            },
-        )
-    }
+/// Loads the verifying key artifact and the params it was exported for.
+fn read_keys(
+    options: &Options,
+) -> Result<(Params<EqAffine>, VkArtifact, VerifyingKey<EqAffine>), String> {
+    let vk_path = options.get_or("vk", DEFAULT_VK);
+    let artifact =
+        VkArtifact::open(vk_path).map_err(|e| format!("cannot load `{}`: {}", vk_path, e))?;
+
+    let params_path = options.get_or("params", DEFAULT_PARAMS);
+    let params = load_params(params_path, artifact.k)
+        .map_err(|e| format!("cannot load `{}`: {}", params_path, e))?;
+
+    let vk = artifact
+        .load(&params)
+        .map_err(|e| format!("cannot load `{}`: {}", vk_path, e))?;
+    Ok((params, artifact, vk))
+}
 
-    fn add(
-        &self,
-        mut layouter: impl Layouter<F>,
-        a: Number<F>,
-        b: Number<F>,
-    ) -> Result<Number<F>, Error> {
-        layouter.assign_region(
-            || "add",
-            |mut region| {
-                self.config.s_add.enable(&mut region, 0)?;
-
-                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
-                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
-
-                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a + *b));
-                region
-                    .assign_advice(
-                        || "lhs + rhs",
-                        self.config.advice[0],
-                        1,
-                        || value,
-                    )
-                    .map(Number)
-            },
-        )
-    }
+fn read_witness(path: &str) -> Result<BTreeMap<String, Fp>, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("cannot read witness `{}`: {}", path, e))?;
 
-    fn expose_public(
-        &self,
-        mut layouter: impl Layouter<F>,
-        num: Number<F>,
-        row: usize,
-    ) -> Result<(), Error> {
-        layouter.constrain_instance(num.0.cell(), self.config.instance, row)
+    let mut witness = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("{}:{}: expected `name = value`", path, i + 1))?;
+        let value = parse_field(value).map_err(|e| format!("{}:{}: {}", path, i + 1, e))?;
+        witness.insert(name.trim().to_string(), value);
     }
+    Ok(witness)
 }
 
-#[derive(Default, Clone)]
-struct MyCircuit<F: FieldExt> {
-    constant: F,
-    a: Value<F>,
-    b: Value<F>,
-    c: Value<F>,
+fn setup_cmd(options: &Options) -> Result<(), String> {
+    let mode = parse_mode(options.get_or("mode", DEFAULT_MODE))?;
+    let k = parse_k(options, || MyCircuit::<Fp>::min_k(mode))?;
+    let constant = parse_field(options.get_or("constant", DEFAULT_CONSTANT))?;
+    let params_path = options.get_or("params", DEFAULT_PARAMS);
+    let vk_path = options.get_or("vk", DEFAULT_VK);
+
+    let params: Params<EqAffine> = Params::new(k);
+    let artifact = VkArtifact::generate(&params, mode, constant)
+        .map_err(|e| format!("keygen failed: {}", e))?;
+
+    save_params(&params, params_path)
+        .map_err(|e| format!("cannot write `{}`: {}", params_path, e))?;
+
+    artifact
+        .save(vk_path)
+        .map_err(|e| format!("cannot write `{}`: {}", vk_path, e))?;
+
+    println!("wrote {} and {} for k = {}", params_path, vk_path, k);
+    Ok(())
+}
+
+fn prove_cmd(options: &Options) -> Result<(), String> {
+    let witness = read_witness(options.get("witness")?)?;
+    let (params, artifact, vk) = read_keys(options)?;
+    let constant = match artifact.mode {
+        ConstantMode::Fixed => {
+            let constant = artifact.constant;
+            if let Some(other) = options.get_optional("constant") {
+                if parse_field(other)? != constant {
+                    return Err(format!(
+                        "the verifying key fixes the constant to {:?}",
+                        constant
+                    ));
+                }
+            }
+            constant
+        }
+        ConstantMode::Public => parse_field(options.get_or("constant", DEFAULT_CONSTANT))?,
+    };
+    let pk = keygen_pk(&params, vk, &MyCircuit::empty(artifact.mode, constant))
+        .map_err(|e| format!("keygen failed: {}", e))?;
+    let proof_path = options.get_or("proof", DEFAULT_PROOF);
+
+    let input = |name: &str| {
+        witness
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("witness is missing `{}`", name))
+    };
+    let (a, b, c) = (input("a")?, input("b")?, input("c")?);
+    let public = MyPublicInputs::new(constant, a, b, c);
+
+    let circuit = MyCircuit {
+        constant,
+        mode: artifact.mode,
+        a: Value::known(a),
+        b: Value::known(b),
+        c: Value::known(c),
+    };
+    let proof = prove(&params, &pk, circuit, &public.to_instance())
+        .map_err(|e| format!("proving failed: {}", e))?;
+    let file = ProofFile {
+        transcript: TranscriptKind::Blake2b,
+        circuit_id: artifact.fingerprint,
+        k: artifact.k,
+        instances: public.to_instance(),
+        proof,
+    };
+    fs::write(proof_path, file.to_bytes())
+        .map_err(|e| format!("cannot write `{}`: {}", proof_path, e))?;
+
+    println!("wrote {}", proof_path);
+    println!("d = {:?}", public.d);
+    println!("H = {:?}", public.h);
+    println!("constant = {:?}", public.constant);
+    Ok(())
 }
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
-    type Config = MyConfig;
+fn verify_cmd(options: &Options) -> Result<(), String> {
+    let (params, artifact, vk) = read_keys(options)?;
+    let proof_path = options.get_or("proof", DEFAULT_PROOF);
+    let bytes =
+        fs::read(proof_path).map_err(|e| format!("cannot read proof `{}`: {}", proof_path, e))?;
+    let file = ProofFile::from_bytes(&bytes, &artifact.fingerprint)
+        .map_err(|e| format!("cannot load `{}`: {}", proof_path, e))?;
+    if file.k != artifact.k {
+        return Err(format!(
+            "proof was created with k = {}, but the verifying key has k = {}",
+            file.k, artifact.k
+        ));
+    }
 
-    type FloorPlanner = SimpleFloorPlanner;
+    let public = MyPublicInputs::from_instance(&file.instances)
+        .map_err(|e| format!("cannot load `{}`: {}", proof_path, e))?;
 
-    fn without_witnesses(&self) -> Self {
-        Self::default()
+    // The public inputs in the proof file come from the prover, so the verifier has to
+    // state the ones that matter: `d`, and the constant when the key does not fix it.
+    let expected = parse_public(options.get("public")?)?;
+    let mut required = vec!["d"];
+    if artifact.mode == ConstantMode::Public {
+        required.push("constant");
+    }
+    for name in required {
+        if !expected.iter().any(|(n, _)| n == name) {
+            return Err(format!("`--public` must give the expected `{}`", name));
+        }
+    }
+    for (name, value) in &expected {
+        let row = MyPublicInputs::<Fp>::row(name).map_err(|e| e.to_string())?;
+        if file.instances[row] != *value {
+            return Err(format!(
+                "public input `{}` does not match the proof file",
+                name
+            ));
+        }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let advice = [meta.advice_column(), meta.advice_column()];
-        let instance = meta.instance_column();
-        let constant = meta.fixed_column();
+    verify(&params, &vk, &file.proof, &file.instances).map_err(|e| e.to_string())?;
 
-        // Enable the ability to enforce equality over cells in each column
-        meta.enable_equality(instance);
-        meta.enable_constant(constant);
-        for adc in &advice {
-            meta.enable_equality(*adc);
+    let source = |name: &str| {
+        if expected.iter().any(|(n, _)| n == name) {
+            ""
+        } else {
+            "  (from the proof file)"
         }
+    };
+    println!("d = {:?}{}", public.d, source("d"));
+    println!("H = {:?}{}", public.h, source("H"));
+    println!("constant = {:?}{}", public.constant, source("constant"));
+    println!("proof is valid");
+    Ok(())
+}
 
-        let s_mul = meta.selector();
-        let s_add = meta.selector();
+/// Compiles a `.circ` program and checks it against a witness with `MockProver`.
+fn check_cmd(options: &Options) -> Result<(), String> {
+    let circuit_path = options.get("circuit")?;
+    let src = fs::read_to_string(circuit_path)
+        .map_err(|e| format!("cannot read circuit `{}`: {}", circuit_path, e))?;
+    let program: Program<Fp> =
+        Program::parse(&src).map_err(|e| format!("{}:{}", circuit_path, e))?;
+    let witness = read_witness(options.get("witness")?)?;
+
+    let missing: Vec<_> = program
+        .inputs
+        .iter()
+        .filter(|name| !witness.contains_key(*name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("witness is missing {}", missing.join(", ")));
+    }
+    let public = program
+        .evaluate(&witness)
+        .map_err(|e| format!("cannot evaluate `{}`: {}", circuit_path, e))?;
+
+    let circuit = program.circuit(&witness);
+    let instance = vec![public.clone()];
+    let k = parse_k(options, || min_k(&circuit, &instance))?;
+
+    let prover =
+        MockProver::run(k, &circuit, instance).map_err(|e| format!("synthesis failed: {}", e))?;
+    prover.verify().map_err(|failures| {
+        failures
+            .iter()
+            .map(|failure| failure.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
+
+    for (name, value) in program.public.iter().zip(&public) {
+        println!("{} = {:?}", name, value);
+    }
+    println!("circuit is satisfied");
+    Ok(())
+}
 
-        meta.create_gate("mul", |cell| {
-            let lhs = cell.query_advice(advice[0], Rotation::cur());
-            let rhs = cell.query_advice(advice[1], Rotation::cur());
-            let out = cell.query_advice(advice[0], Rotation::next());
-            let s_mul = cell.query_selector(s_mul);
+/// Prints the `CircuitStats` of `MyCircuit`.
+fn stats_cmd(options: &Options) -> Result<(), String> {
+    let mode = parse_mode(options.get_or("mode", DEFAULT_MODE))?;
+    let k = parse_k(options, || MyCircuit::<Fp>::min_k(mode))?;
+    let constant = parse_field(options.get_or("constant", DEFAULT_CONSTANT))?;
 
-            vec![(lhs * rhs - out) * s_mul]
-        });
+    // The layout does not depend on the witness or the public inputs.
+    let circuit = MyCircuit::empty(mode, constant);
+    let instance = vec![vec![Fp::zero(); MyPublicInputs::<Fp>::NAMES.len()]];
+    let stats = CircuitStats::measure(k, &circuit, &instance).map_err(|e| e.to_string())?;
 
-        meta.create_gate("add", |cell| {
-            let lhs = cell.query_advice(advice[0], Rotation::cur());
-            let rhs = cell.query_advice(advice[1], Rotation::cur());
-            let out = cell.query_advice(advice[0], Rotation::next());
-            let s_add = cell.query_selector(s_add);
+    println!("{}", stats);
+    Ok(())
+}
 
-            vec![(lhs + rhs - out) * s_add]
-        });
+/// `MyCircuit` for `--mode` and `--constant` at `--k`, with its public inputs.
+///
+/// With `--witness` the circuit takes `a`, `b` and `c` from the file; otherwise it has no
+/// witness and the public inputs are zero.
+fn my_circuit(options: &Options) -> Result<(u32, MyCircuit<Fp>, Vec<Fp>), String> {
+    let mode = parse_mode(options.get_or("mode", DEFAULT_MODE))?;
+    let k = parse_k(options, || MyCircuit::<Fp>::min_k(mode))?;
+    let constant = parse_field(options.get_or("constant", DEFAULT_CONSTANT))?;
+
+    let path = match options.get_optional("witness") {
+        Some(path) => path,
+        None => {
+            let public = vec![Fp::zero(); MyPublicInputs::<Fp>::NAMES.len()];
+            return Ok((k, MyCircuit::empty(mode, constant), public));
+        }
+    };
+    let witness = read_witness(path)?;
+    let input = |name: &str| {
+        witness
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("witness is missing `{}`", name))
+    };
+    let (a, b, c) = (input("a")?, input("b")?, input("c")?);
+    let circuit = MyCircuit {
+        constant,
+        mode,
+        a: Value::known(a),
+        b: Value::known(b),
+        c: Value::known(c),
+    };
+    Ok((
+        k,
+        circuit,
+        MyPublicInputs::new(constant, a, b, c).to_instance(),
+    ))
+}
 
-        Self::Config {
-            advice,
-            instance,
-            s_mul,
-            s_add,
+/// Prints or writes the `Trace` of `MyCircuit`.
+fn trace_cmd(options: &Options) -> Result<(), String> {
+    let (k, circuit, public) = my_circuit(options)?;
+    let trace = Trace::new(k, &circuit, &[public]).map_err(|e| e.to_string())?;
+
+    let output = match options.get_or("format", "text") {
+        "text" => trace.to_string(),
+        "json" => trace.to_json(),
+        format => {
+            return Err(format!(
+                "`--format` must be `text` or `json`, found `{}`",
+                format
+            ))
+        }
+    };
+    match options.get_optional("output") {
+        Some(path) => {
+            fs::write(path, output).map_err(|e| format!("cannot write `{}`: {}", path, e))?;
+            println!("wrote {}", path);
         }
+        None => print!("{}", output),
     }
+    Ok(())
+}
 
-    fn synthesize(
-        &self,
-        config: Self::Config,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        let chip = MyChip::new(config);
-
-        let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
-        let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
-        let c = chip.load_private(layouter.namespace(|| "load c"), self.c)?;
-
-        let constant = chip.load_constant(layouter.namespace(|| "load constant"), self.constant)?;
-
-        let aa = chip.mul(layouter.namespace(|| "a * a"), a.clone(), a)?;
-        let bc = chip.mul(layouter.namespace(|| "b * c"), b, c)?;
-        let aa_bc = chip.add(layouter.namespace(|| "a^2 + b*c"), aa, bc)?;
-        let d = chip.mul(
-            layouter.namespace(|| "constant * (a^2 + b * c)"),
-            constant,
-            aa_bc,
-        )?;
-
-        chip.expose_public(layouter.namespace(|| "expose d"), d, 0)
+/// Writes the assignment table of `MyCircuit` as CSV, or as XLSX for a `.xlsx` output.
+fn export_cmd(options: &Options) -> Result<(), String> {
+    let (k, circuit, public) = my_circuit(options)?;
+    let table = WitnessTable::new(k, &circuit, &[public]).map_err(|e| e.to_string())?;
+
+    let path = match options.get_optional("output") {
+        Some(path) => path,
+        None => {
+            return table
+                .write_csv(io::stdout().lock())
+                .map_err(|e| format!("cannot write the table: {}", e))
+        }
+    };
+    let file = fs::File::create(path).map_err(|e| format!("cannot write `{}`: {}", path, e))?;
+    let file = io::BufWriter::new(file);
+    if path.ends_with(".xlsx") {
+        write_xlsx(&table, file)
+    } else {
+        table.write_csv(file)
     }
+    .map_err(|e| format!("cannot write `{}`: {}", path, e))?;
+    println!("wrote {}", path);
+    Ok(())
 }
 
-fn main() {
-    use std::time::Instant;
-    use halo2_proofs::poly::{commitment::Params, Rotation};
-    use halo2_proofs::pasta::{Eq, EqAffine, Fp};
-    use halo2_proofs::plonk::{
-        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Assigned, BatchVerifier, Circuit,
-        Column, ConstraintSystem, Error, Fixed, SingleVerifier, TableColumn, VerificationStrategy,
+#[cfg(feature = "xlsx")]
+fn write_xlsx(table: &WitnessTable, file: impl io::Write) -> io::Result<()> {
+    table.write_xlsx(file)
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn write_xlsx(_table: &WitnessTable, _file: impl io::Write) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "XLSX output needs the `xlsx` feature",
+    ))
+}
+
+/// Draws the layout of `MyCircuit` to `--output`, and writes its namespaces as DOT to
+/// `--dot`.
+#[cfg(feature = "layout")]
+fn layout_cmd(options: &Options) -> Result<(), String> {
+    let mode = parse_mode(options.get_or("mode", DEFAULT_MODE))?;
+    let k = parse_k(options, || MyCircuit::<Fp>::min_k(mode))?;
+    let constant = parse_field(options.get_or("constant", DEFAULT_CONSTANT))?;
+
+    let defaults = LayoutOptions::default();
+    let pixels = |name: &str, default: u32| match options.get_optional(name) {
+        Some(n) => n
+            .parse()
+            .map_err(|_| format!("`--{}` must be a positive integer", name)),
+        None => Ok(default),
+    };
+    let range = |name: &str| options.get_optional(name).map(|s| parse_range(name, s));
+    let layout = LayoutOptions {
+        width: pixels("width", defaults.width)?,
+        height: pixels("height", defaults.height)?,
+        title: options.get_optional("title").map(str::to_string),
+        view_width: range("columns").transpose()?,
+        view_height: range("rows").transpose()?,
+        show_labels: match options.get_or("labels", "true") {
+            "true" => true,
+            "false" => false,
+            s => {
+                return Err(format!(
+                    "`--labels` must be `true` or `false`, found `{}`",
+                    s
+                ))
+            }
+        },
+        ..defaults
     };
-    use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge};
-    use rand_core::OsRng;
 
+    // The layout does not depend on the witness.
+    let circuit = MyCircuit::empty(mode, constant);
+    let path = options.get_or("output", DEFAULT_LAYOUT);
+    render_layout(k, &circuit, &layout, Path::new(path))
+        .map_err(|e| format!("cannot write `{}`: {}", path, e))?;
+    println!("wrote {}", path);
+
+    if let Some(path) = options.get_optional("dot") {
+        fs::write(path, layout_dot(&circuit))
+            .map_err(|e| format!("cannot write `{}`: {}", path, e))?;
+        println!("wrote {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "layout"))]
+fn layout_cmd(_options: &Options) -> Result<(), String> {
+    Err("`layout` needs the `layout` feature".to_string())
+}
+
+/// Parses a `start..end` range for `--<name>`.
+#[cfg(feature = "layout")]
+fn parse_range(name: &str, s: &str) -> Result<Range<usize>, String> {
+    let invalid = || format!("`--{}` must be a range `start..end`, found `{}`", name, s);
+    let (start, end) = s.split_once("..").ok_or_else(invalid)?;
+    let start = start.trim().parse().map_err(|_| invalid())?;
+    let end = end.trim().parse().map_err(|_| invalid())?;
+    if start >= end {
+        return Err(invalid());
+    }
+    Ok(start..end)
+}
+
+fn demo() {
     let a = Fp::from(10);
     let b = Fp::from(5);
     let c = Fp::from(3);
     let constant = Fp::from(2);
 
     // (100 + 15) * 2 = 115 * 2 = 230
-    let d = Fp::from(((u32::pow(10, 2) + 5 * 3) * 2) as u64);
+    let public = MyPublicInputs::new(constant, a, b, c);
+    assert_eq!(public.d, Fp::from(((u32::pow(10, 2) + 5 * 3) * 2) as u64));
+    let public_input = public.to_instance();
 
     let circuit = MyCircuit {
         constant,
+        mode: ConstantMode::Fixed,
         a: Value::known(a),
         b: Value::known(b),
         c: Value::known(c),
     };
 
-    let empty_circuit = MyCircuit {
-        constant,
-        a: Value::unknown(),
-        b: Value::unknown(),
-        c: Value::unknown(),
-    };
-
-    let public_input = vec![d];
-    let k = 5;
-    let params: Params<EqAffine> = Params::new(k);
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).expect("the circuit should fit");
+    println!("k = {}", k);
 
     // check circuit.
-    let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+    let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
     assert_eq!(prover.verify(), Ok(()));
 
+    // Draw the circuit layout, and its namespaces as a DOT graph.
+    #[cfg(feature = "layout")]
+    {
+        let layout = LayoutOptions {
+            title: Some("Example Circuit Layout".to_string()),
+            ..LayoutOptions::default()
+        };
+        render_layout(k, &circuit, &layout, Path::new("layout.png")).expect("layout should render");
+        fs::write("layout.dot", layout_dot(&circuit)).expect("layout.dot should be writable");
+        println!("wrote layout.png and layout.dot");
+    }
+
     // create proof
     let now = Instant::now();
 
-    let vk = keygen_vk(&params, &empty_circuit).expect("keygen_vk should not fail");
-    let pk = keygen_pk(&params, vk, &empty_circuit).expect("keygen_pk should not fail");
-    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
-        // Create a proof
-        create_proof(
-            &params,
-            &pk,
-            &[circuit],
-            &[&[&[d]]],
-            OsRng,
-            &mut transcript,
-        )
-        .expect("proof generation should not fail");
-    let _proof: Vec<u8> = transcript.finalize();
+    let (params, pk) = setup(ConstantMode::Fixed, constant).expect("keygen should not fail");
+    let proof =
+        prove(&params, &pk, circuit, &public_input).expect("proof generation should not fail");
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
 
-    // Create the area you want to draw on.
-    // Use SVGBackend if you want to render to .svg instead.
-    
-    // let root = BitMapBackend::new("layout.png", (1024, 768)).into_drawing_area();
-    // root.fill(&WHITE).unwrap();
-    // let root = root
-    //     .titled("Example Circuit Layout", ("sans-serif", 60))
-    //     .unwrap();
-
-    // halo2_proofs::dev::CircuitLayout::default()
-    //     // You can optionally render only a section of the circuit.
-    //     .view_width(0..2)
-    //     .view_height(0..16)
-    //     // You can hide labels, which can be useful with smaller areas.
-    //     .show_labels(true)
-    //     // Render the circuit onto your area!
-    //     // The first argument is the size parameter for the circuit.
-    //     .render(5, &circuit, &root)
-    //     .unwrap();
-
-
-    // // Generate the DOT graph string.
-    // let dot_string = halo2_proofs::dev::circuit_dot_graph(&circuit);
-
-    // // Now you can either handle it in Rust, or just
-    // // print it out to use with command-line tools.
-    // print!("{}", dot_string);
+    // verify proof
+    let now = Instant::now();
+    verify(&params, pk.get_vk(), &proof, &public_input).expect("proof should verify");
+    let elapsed = now.elapsed();
+    println!("Verified in: {:.2?}", elapsed);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, allowed): (Command, &[&str]) = match args.first().map(String::as_str) {
+        Some("setup") => (setup_cmd, &["k", "mode", "constant", "params", "vk"]),
+        Some("prove") => (prove_cmd, &["witness", "constant", "params", "vk", "proof"]),
+        Some("verify") => (verify_cmd, &["public", "params", "vk", "proof"]),
+        Some("check") => (check_cmd, &["circuit", "witness", "k"]),
+        Some("stats") => (stats_cmd, &["k", "mode", "constant"]),
+        Some("trace") => (
+            trace_cmd,
+            &["witness", "k", "mode", "constant", "format", "output"],
+        ),
+        Some("export") => (export_cmd, &["witness", "k", "mode", "constant", "output"]),
+        Some("layout") => (
+            layout_cmd,
+            &[
+                "k", "mode", "constant", "output", "dot", "width", "height", "columns", "rows",
+                "labels", "title",
+            ],
+        ),
+        Some("demo") => {
+            demo();
+            return;
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = Options::parse(&args[1..], allowed).and_then(|options| command(&options));
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
 }
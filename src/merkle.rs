@@ -0,0 +1,183 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    chip::{MyChip, Number},
+    circuit::{MyCircuit, MyCircuitConfig},
+    poseidon::{poseidon_hash, PoseidonChip},
+};
+
+/// One step of a Merkle path: the sibling node, and whether the current node is the
+/// right child.
+pub type PathStep<F> = (F, bool);
+
+/// The parent of `left` and `right`.
+pub fn merkle_parent<F: FieldExt>(left: F, right: F) -> F {
+    poseidon_hash(&[left, right])
+}
+
+/// The root reached from `leaf` along `path`, leaf level first.
+pub fn merkle_root<F: FieldExt>(leaf: F, path: &[PathStep<F>]) -> F {
+    path.iter().fold(leaf, |node, (sibling, is_right)| {
+        if *is_right {
+            merkle_parent(*sibling, node)
+        } else {
+            merkle_parent(node, *sibling)
+        }
+    })
+}
+
+/// A complete binary Merkle tree over Poseidon, with missing leaves set to zero.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<F: FieldExt> {
+    /// The levels of the tree, leaves first and the root last.
+    levels: Vec<Vec<F>>,
+}
+
+impl<F: FieldExt> MerkleTree<F> {
+    /// Builds a tree of the given `depth`, holding up to `2^depth` leaves.
+    pub fn new(depth: usize, leaves: &[F]) -> Self {
+        assert!(
+            leaves.len() <= 1 << depth,
+            "too many leaves for depth {}",
+            depth
+        );
+        let mut level = leaves.to_vec();
+        level.resize(1 << depth, F::zero());
+
+        let mut levels = vec![level];
+        for _ in 0..depth {
+            let parents = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| merkle_parent(pair[0], pair[1]))
+                .collect();
+            levels.push(parents);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn root(&self) -> F {
+        self.levels[self.depth()][0]
+    }
+
+    /// The path from the leaf at `index` to the root.
+    pub fn path(&self, index: usize) -> Vec<PathStep<F>> {
+        self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| {
+                let node = index >> height;
+                (level[node ^ 1], node & 1 == 1)
+            })
+            .collect()
+    }
+}
+
+/// Lays out the path from `leaf` to the root and returns the root.
+///
+/// Each direction is `1` if the current node is the right child, and is constrained to
+/// be boolean by the "select" gate.
+pub fn assign_merkle_root<F: FieldExt>(
+    chip: &MyChip<F>,
+    poseidon: &PoseidonChip<F>,
+    mut layouter: impl Layouter<F>,
+    leaf: Number<F>,
+    path: &[(Number<F>, Number<F>)],
+) -> Result<Number<F>, Error> {
+    let mut node = leaf;
+    for (height, (sibling, is_right)) in path.iter().enumerate() {
+        let mut layouter = layouter.namespace(|| format!("level {}", height));
+        let left = chip.select(
+            layouter.namespace(|| "left"),
+            is_right.clone(),
+            sibling.clone(),
+            node.clone(),
+        )?;
+        let right = chip.select(
+            layouter.namespace(|| "right"),
+            is_right.clone(),
+            node,
+            sibling.clone(),
+        )?;
+        node = poseidon.hash(layouter.namespace(|| "parent"), &[left, right])?;
+    }
+    Ok(node)
+}
+
+/// A circuit proving that the private `leaf` is in a Merkle tree whose root is the
+/// public instance at row 0. The depth is the length of `path`.
+#[derive(Clone, Debug)]
+pub struct MerkleCircuit<F: FieldExt> {
+    pub leaf: Value<F>,
+    pub path: Vec<(Value<F>, Value<bool>)>,
+}
+
+impl<F: FieldExt> MerkleCircuit<F> {
+    pub fn new(leaf: F, path: &[PathStep<F>]) -> Self {
+        MerkleCircuit {
+            leaf: Value::known(leaf),
+            path: path
+                .iter()
+                .map(|(sibling, is_right)| (Value::known(*sibling), Value::known(*is_right)))
+                .collect(),
+        }
+    }
+
+    /// A circuit for trees of the given `depth` without a witness, for key generation.
+    pub fn empty(depth: usize) -> Self {
+        MerkleCircuit {
+            leaf: Value::unknown(),
+            path: vec![(Value::unknown(), Value::unknown()); depth],
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for MerkleCircuit<F> {
+    type Config = MyCircuitConfig;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::empty(self.path.len())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MyChip::new(config.chip);
+        let poseidon = PoseidonChip::new(config.poseidon);
+
+        let leaf = chip.load_private(layouter.namespace(|| "load leaf"), self.leaf)?;
+        let path = self
+            .path
+            .iter()
+            .enumerate()
+            .map(|(height, (sibling, is_right))| {
+                let mut layouter = layouter.namespace(|| format!("load level {}", height));
+                let sibling = chip.load_private(layouter.namespace(|| "sibling"), *sibling)?;
+                let is_right = is_right.map(|is_right| F::from(is_right as u64));
+                let is_right = chip.load_private(layouter.namespace(|| "direction"), is_right)?;
+                Ok((sibling, is_right))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let root =
+            assign_merkle_root(&chip, &poseidon, layouter.namespace(|| "root"), leaf, &path)?;
+        chip.expose_public(layouter.namespace(|| "expose root"), root, 0)
+    }
+}
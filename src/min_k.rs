@@ -0,0 +1,25 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    dev::MockProver,
+    plonk::{Circuit, Error},
+};
+
+/// The largest `k` tried by [`min_k`].
+pub const MAX_K: u32 = 20;
+
+/// The smallest `k` for which `circuit` can be synthesized with `instance`.
+///
+/// This runs `MockProver` with increasing `k`. `MockProver` keeps the blinding rows at the
+/// end of every column out of reach, exactly like the real prover, so a circuit that fits
+/// here also fits in params of size `2^k`. The circuit needs a witness, although any
+/// witness that synthesizes will do: the layout does not depend on the values.
+pub fn min_k<F: FieldExt, C: Circuit<F>>(circuit: &C, instance: &[Vec<F>]) -> Result<u32, Error> {
+    for k in 1..=MAX_K {
+        match MockProver::run(k, circuit, instance.to_vec()) {
+            Ok(_) => return Ok(k),
+            Err(Error::NotEnoughRowsAvailable { .. }) | Err(Error::InstanceTooLarge) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(Error::NotEnoughRowsAvailable { current_k: MAX_K })
+}
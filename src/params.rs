@@ -0,0 +1,116 @@
+use halo2_proofs::{pasta::EqAffine, poly::commitment::Params};
+use std::{fmt, fs, io, path::Path};
+
+/// Identifies a params file written by [`write_params`].
+const MAGIC: [u8; 8] = *b"h2hi-prm";
+
+/// Length of the Blake2b checksum over the serialized params.
+const CHECKSUM_LEN: usize = 32;
+
+#[derive(Debug)]
+pub enum ParamsError {
+    /// The params could not be read or written.
+    Io(io::Error),
+    /// The file does not start with the params magic bytes.
+    BadMagic,
+    /// The stored params do not match their checksum.
+    ChecksumMismatch,
+    /// The stored params were generated for a different `k` than the circuit needs.
+    WrongK { expected: u32, found: u32 },
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamsError::Io(e) => write!(f, "{}", e),
+            ParamsError::BadMagic => write!(f, "not a params file"),
+            ParamsError::ChecksumMismatch => write!(f, "params checksum mismatch"),
+            ParamsError::WrongK { expected, found } => write!(
+                f,
+                "params were generated for k = {}, but the circuit needs k = {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+impl From<io::Error> for ParamsError {
+    fn from(error: io::Error) -> Self {
+        ParamsError::Io(error)
+    }
+}
+
+/// The `k` that `params` were generated for.
+pub(crate) fn params_k(params: &Params<EqAffine>) -> u32 {
+    params.get_g().len().trailing_zeros()
+}
+
+fn checksum(bytes: &[u8]) -> blake2b_simd::Hash {
+    blake2b_simd::Params::new()
+        .hash_length(CHECKSUM_LEN)
+        .personal(b"halo2_hi-params")
+        .hash(bytes)
+}
+
+/// Writes `params` preceded by a header holding `k` and a checksum of the params.
+pub fn write_params<W: io::Write>(params: &Params<EqAffine>, writer: &mut W) -> io::Result<()> {
+    let mut bytes = vec![];
+    params.write(&mut bytes)?;
+
+    // `Params::write` starts with `k`, which is not otherwise exposed.
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&bytes[..4])?;
+    writer.write_all(checksum(&bytes).as_bytes())?;
+    writer.write_all(&bytes)
+}
+
+/// Reads params written by [`write_params`], checking that they were generated for
+/// `expected_k` and that they match their checksum.
+pub fn read_params<R: io::Read>(
+    reader: &mut R,
+    expected_k: u32,
+) -> Result<Params<EqAffine>, ParamsError> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(ParamsError::BadMagic);
+    }
+
+    let mut k = [0u8; 4];
+    reader.read_exact(&mut k)?;
+    let k = u32::from_le_bytes(k);
+    if k != expected_k {
+        return Err(ParamsError::WrongK {
+            expected: expected_k,
+            found: k,
+        });
+    }
+
+    let mut expected_checksum = [0u8; CHECKSUM_LEN];
+    reader.read_exact(&mut expected_checksum)?;
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    if checksum(&bytes).as_bytes() != expected_checksum || !bytes.starts_with(&k.to_le_bytes()) {
+        return Err(ParamsError::ChecksumMismatch);
+    }
+
+    Ok(Params::read(&mut &bytes[..])?)
+}
+
+/// Writes `params` to the file at `path`, see [`write_params`].
+pub fn save_params(params: &Params<EqAffine>, path: impl AsRef<Path>) -> io::Result<()> {
+    let mut bytes = vec![];
+    write_params(params, &mut bytes)?;
+    fs::write(path, bytes)
+}
+
+/// Reads params from the file at `path`, see [`read_params`].
+pub fn load_params(
+    path: impl AsRef<Path>,
+    expected_k: u32,
+) -> Result<Params<EqAffine>, ParamsError> {
+    let bytes = fs::read(path)?;
+    read_params(&mut &bytes[..], expected_k)
+}
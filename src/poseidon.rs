@@ -0,0 +1,447 @@
+//! A Poseidon hash over a width-3 state, with the parameters of `P128Pow5T3`: x^5
+//! S-boxes, 8 full and 56 partial rounds, and round constants and MDS matrix derived
+//! from the Grain LFSR as in the reference implementation.
+//!
+//! Inputs are hashed with a constant-length sponge of rate 2: the capacity element
+//! encodes the number of inputs, and the last block is padded with zeros.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::chip::Number;
+
+pub const WIDTH: usize = 3;
+pub const RATE: usize = 2;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 56;
+const ROUNDS: usize = FULL_ROUNDS + PARTIAL_ROUNDS;
+
+type State<F> = [F; WIDTH];
+
+/// Round constants and MDS matrix for the permutation.
+#[derive(Clone, Debug)]
+struct Constants<F: FieldExt> {
+    round_constants: Vec<State<F>>,
+    mds: [State<F>; WIDTH],
+}
+
+impl<F: FieldExt> Constants<F> {
+    /// The constants for `F`, derived on first use and shared afterwards.
+    fn get() -> Arc<Self> {
+        type Cache = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+        static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+        let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+        cache
+            .entry(TypeId::of::<F>())
+            .or_insert_with(|| Box::new(Arc::new(Self::generate())))
+            .downcast_ref::<Arc<Self>>()
+            .expect("the cache is keyed by field")
+            .clone()
+    }
+
+    fn generate() -> Self {
+        let mut grain = Grain::new(WIDTH, FULL_ROUNDS, PARTIAL_ROUNDS, F::NUM_BITS as usize);
+        let round_constants = (0..ROUNDS)
+            .map(|_| [(); WIDTH].map(|_| grain.next_field_element()))
+            .collect();
+
+        // A Cauchy matrix over 2 * WIDTH distinct elements. The first one drawn is
+        // secure for these parameters.
+        let (xs, ys) = loop {
+            let values: Vec<F> = (0..2 * WIDTH)
+                .map(|_| grain.next_field_element_without_rejection())
+                .collect();
+            let mut unique = values.clone();
+            unique.sort_unstable_by_key(|v| v.to_repr().as_ref().to_vec());
+            unique.dedup();
+            if unique.len() == values.len() {
+                break (values[..WIDTH].to_vec(), values[WIDTH..].to_vec());
+            }
+        };
+        let mds =
+            std::array::from_fn(|i| std::array::from_fn(|j| (xs[i] + ys[j]).invert().unwrap()));
+
+        Constants {
+            round_constants,
+            mds,
+        }
+    }
+
+    fn is_full_round(round: usize) -> bool {
+        let half = FULL_ROUNDS / 2;
+        !(half..half + PARTIAL_ROUNDS).contains(&round)
+    }
+
+    /// Applies round `round` to `state`.
+    fn round(&self, round: usize, state: &State<F>) -> State<F> {
+        let rc = &self.round_constants[round];
+        let mut words: State<F> = std::array::from_fn(|i| state[i] + rc[i]);
+        for (i, word) in words.iter_mut().enumerate() {
+            if i == 0 || Self::is_full_round(round) {
+                *word = pow5(*word);
+            }
+        }
+        self.mds
+            .map(|row| (0..WIDTH).fold(F::zero(), |acc, j| acc + row[j] * words[j]))
+    }
+
+    fn permute(&self, state: &mut State<F>) {
+        for round in 0..ROUNDS {
+            *state = self.round(round, state);
+        }
+    }
+}
+
+fn pow5<F: FieldExt>(x: F) -> F {
+    x.square().square() * x
+}
+
+/// The capacity element for hashing `len` inputs.
+fn initial_capacity<F: FieldExt>(len: usize) -> F {
+    F::from_u128((len as u128) << 64)
+}
+
+/// Hashes `inputs` natively.
+pub fn poseidon_hash<F: FieldExt>(inputs: &[F]) -> F {
+    let constants = Constants::get();
+    let mut state = [F::zero(), F::zero(), initial_capacity(inputs.len())];
+    for block in blocks(inputs.len()) {
+        for (i, index) in block.into_iter().enumerate() {
+            if let Some(index) = index {
+                state[i] += inputs[index];
+            }
+        }
+        constants.permute(&mut state);
+    }
+    state[0]
+}
+
+/// Applies the Poseidon permutation to `state` natively.
+pub fn poseidon_permute<F: FieldExt>(state: &mut [F; WIDTH]) {
+    Constants::get().permute(state);
+}
+
+/// Splits `len` inputs into zero-padded blocks of `RATE` input indices.
+fn blocks(len: usize) -> Vec<[Option<usize>; RATE]> {
+    (0..len.div_ceil(RATE).max(1))
+        .map(|block| std::array::from_fn(|i| Some(block * RATE + i).filter(|index| *index < len)))
+        .collect()
+}
+
+/// The Grain LFSR used to derive Poseidon parameters.
+struct Grain {
+    state: [bool; 80],
+}
+
+impl Grain {
+    fn new(width: usize, full_rounds: usize, partial_rounds: usize, num_bits: usize) -> Self {
+        let mut state = [true; 80];
+        // Prime field, x^alpha S-box, then the field size and round numbers, MSB first.
+        let fields = [
+            (2, 1),
+            (4, 0),
+            (12, num_bits),
+            (12, width),
+            (10, full_rounds),
+            (10, partial_rounds),
+        ];
+        let mut offset = 0;
+        for (len, value) in fields {
+            for i in 0..len {
+                state[offset + len - 1 - i] = (value >> i) & 1 != 0;
+            }
+            offset += len;
+        }
+
+        let mut grain = Grain { state };
+        // Discard the first 160 bits.
+        for _ in 0..160 {
+            grain.next_raw_bit();
+        }
+        grain
+    }
+
+    fn next_raw_bit(&mut self) -> bool {
+        let s = &self.state;
+        let new_bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.rotate_left(1);
+        self.state[79] = new_bit;
+        new_bit
+    }
+
+    /// Self-shrinking output: of each pair of bits, the second is kept if the first is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.next_raw_bit();
+            let bit = self.next_raw_bit();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// The next `num_bits` bits as a little-endian integer, read most significant first.
+    fn next_bytes<F: FieldExt>(&mut self) -> [u8; 64] {
+        let num_bits = F::NUM_BITS as usize;
+        let mut bytes = [0u8; 64];
+        for i in (0..num_bits).rev() {
+            if self.next_bit() {
+                bytes[i / 8] |= 1 << (i % 8);
+            }
+        }
+        bytes
+    }
+
+    /// The next field element, rejecting integers not below the modulus.
+    fn next_field_element<F: FieldExt>(&mut self) -> F {
+        loop {
+            let bytes = self.next_bytes::<F>();
+            let mut repr = F::Repr::default();
+            let len = repr.as_ref().len();
+            repr.as_mut().copy_from_slice(&bytes[..len]);
+            if let Some(value) = Option::from(F::from_repr(repr)) {
+                break value;
+            }
+        }
+    }
+
+    /// The next field element, reducing integers modulo the field.
+    fn next_field_element_without_rejection<F: FieldExt>(&mut self) -> F {
+        F::from_bytes_wide(&self.next_bytes::<F>())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PoseidonConfig {
+    pub state: [Column<Advice>; WIDTH],
+    pub round_constants: [Column<Fixed>; WIDTH],
+    pub s_absorb: Selector,
+    pub s_full: Selector,
+    pub s_partial: Selector,
+}
+
+pub struct PoseidonChip<F: FieldExt> {
+    config: PoseidonConfig,
+    constants: Arc<Constants<F>>,
+}
+
+impl<F: FieldExt> PoseidonChip<F> {
+    pub fn new(config: PoseidonConfig) -> Self {
+        PoseidonChip {
+            config,
+            constants: Constants::get(),
+        }
+    }
+
+    /// Configures the absorption and round gates over the given columns.
+    ///
+    /// A fixed column must have been enabled for constants, e.g. by `MyChip::configure`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        state: [Column<Advice>; WIDTH],
+        round_constants: [Column<Fixed>; WIDTH],
+    ) -> PoseidonConfig {
+        for column in &state {
+            meta.enable_equality(*column);
+        }
+
+        let s_absorb = meta.selector();
+        let s_full = meta.selector();
+        let s_partial = meta.selector();
+        let constants = Constants::<F>::get();
+
+        // Rows: previous state, inputs, absorbed state. The capacity is carried over.
+        meta.create_gate("absorb", |cell| {
+            let s_absorb = cell.query_selector(s_absorb);
+
+            (0..WIDTH)
+                .map(|i| {
+                    let prev = cell.query_advice(state[i], Rotation::cur());
+                    let next = cell.query_advice(state[i], Rotation(2));
+                    if i < RATE {
+                        let input = cell.query_advice(state[i], Rotation::next());
+                        (prev + input - next) * s_absorb.clone()
+                    } else {
+                        (prev - next) * s_absorb.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+
+        for (name, selector, full) in [
+            ("full round", s_full, true),
+            ("partial round", s_partial, false),
+        ] {
+            meta.create_gate(name, |cell| {
+                let s = cell.query_selector(selector);
+                let words: Vec<_> = (0..WIDTH)
+                    .map(|i| {
+                        let word = cell.query_advice(state[i], Rotation::cur())
+                            + cell.query_fixed(round_constants[i], Rotation::cur());
+                        if i == 0 || full {
+                            word.clone() * word.clone() * word.clone() * word.clone() * word
+                        } else {
+                            word
+                        }
+                    })
+                    .collect();
+
+                (0..WIDTH)
+                    .map(|i| {
+                        let next = cell.query_advice(state[i], Rotation::next());
+                        let mixed = words
+                            .iter()
+                            .zip(constants.mds[i])
+                            .fold(Expression::Constant(F::zero()), |acc, (word, m)| {
+                                acc + word.clone() * m
+                            });
+                        (mixed - next) * s.clone()
+                    })
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        PoseidonConfig {
+            state,
+            round_constants,
+            s_absorb,
+            s_full,
+            s_partial,
+        }
+    }
+
+    /// Hashes `inputs`, matching [`poseidon_hash`].
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: &[Number<F>],
+    ) -> Result<Number<F>, Error> {
+        let mut state = None;
+        for (i, block) in blocks(inputs.len()).into_iter().enumerate() {
+            state = Some(self.absorb_and_permute(
+                layouter.namespace(|| format!("block {}", i)),
+                state,
+                inputs.len(),
+                block.map(|index| index.map(|index| &inputs[index])),
+            )?);
+        }
+        let [out, _, _] = state.expect("there is at least one block");
+        Ok(Number(out))
+    }
+
+    /// Adds `block` to `state`, or to the initial state if `None`, and permutes it.
+    fn absorb_and_permute(
+        &self,
+        mut layouter: impl Layouter<F>,
+        state: Option<[AssignedCell<F, F>; WIDTH]>,
+        len: usize,
+        block: [Option<&Number<F>>; RATE],
+    ) -> Result<[AssignedCell<F, F>; WIDTH], Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "absorb and permute",
+            |mut region| {
+                config.s_absorb.enable(&mut region, 0)?;
+
+                let mut words = Vec::with_capacity(WIDTH);
+                for i in 0..WIDTH {
+                    let word = match &state {
+                        Some(state) => state[i].copy_advice(
+                            || format!("state {}", i),
+                            &mut region,
+                            config.state[i],
+                            0,
+                        )?,
+                        None => {
+                            let initial = if i < RATE {
+                                F::zero()
+                            } else {
+                                initial_capacity(len)
+                            };
+                            region.assign_advice_from_constant(
+                                || format!("initial {}", i),
+                                config.state[i],
+                                0,
+                                initial,
+                            )?
+                        }
+                    };
+                    words.push(word.value().copied());
+                }
+                for (i, input) in block.iter().enumerate() {
+                    let input = match input {
+                        Some(input) => input.0.copy_advice(
+                            || format!("input {}", i),
+                            &mut region,
+                            config.state[i],
+                            1,
+                        )?,
+                        None => region.assign_advice_from_constant(
+                            || "padding",
+                            config.state[i],
+                            1,
+                            F::zero(),
+                        )?,
+                    };
+                    words[i] = words[i] + input.value();
+                }
+
+                let mut cells = self.assign_state(&mut region, 2, &words)?;
+                for round in 0..ROUNDS {
+                    let row = 2 + round;
+                    if Constants::<F>::is_full_round(round) {
+                        config.s_full.enable(&mut region, row)?;
+                    } else {
+                        config.s_partial.enable(&mut region, row)?;
+                    }
+                    for (i, rc) in self.constants.round_constants[round].iter().enumerate() {
+                        region.assign_fixed(
+                            || format!("round {} constant {}", round, i),
+                            config.round_constants[i],
+                            row,
+                            || Value::known(*rc),
+                        )?;
+                    }
+
+                    let current: Value<Vec<F>> =
+                        cells.iter().map(|cell| cell.value().copied()).collect();
+                    let next = current.map(|words| {
+                        self.constants
+                            .round(round, &std::array::from_fn(|i| words[i]))
+                    });
+                    let words: Vec<_> = (0..WIDTH).map(|i| next.map(|next| next[i])).collect();
+                    cells = self.assign_state(&mut region, row + 1, &words)?;
+                }
+                Ok(cells)
+            },
+        )
+    }
+
+    fn assign_state(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        words: &[Value<F>],
+    ) -> Result<[AssignedCell<F, F>; WIDTH], Error> {
+        let mut cells = Vec::with_capacity(WIDTH);
+        for (i, word) in words.iter().enumerate() {
+            cells.push(region.assign_advice(
+                || format!("state {}", i),
+                self.config.state[i],
+                row,
+                || *word,
+            )?);
+        }
+        cells.try_into().map_err(|_| Error::Synthesis)
+    }
+}
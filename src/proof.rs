@@ -0,0 +1,260 @@
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, BatchVerifier, Error, ProvingKey,
+        SingleVerifier, VerifyingKey,
+    },
+    poly::commitment::Params,
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand_core::OsRng;
+
+use crate::{
+    circuit::{ConstantMode, MyCircuit, MyPublicInputs},
+    instance::{InstanceError, PublicInputs},
+};
+
+/// A proof for `MyCircuit`, serialized through a Blake2b transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof(Vec<u8>);
+
+impl Proof {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for Proof {
+    fn from(bytes: Vec<u8>) -> Self {
+        Proof(bytes)
+    }
+}
+
+#[derive(Debug)]
+pub enum ProveError {
+    /// The public inputs do not match the instance layout of the circuit.
+    Instance(InstanceError),
+    /// A batch has a different number of circuits and instance columns.
+    BatchSize { circuits: usize, instances: usize },
+    /// Any error reported by the prover.
+    Plonk(Error),
+}
+
+impl std::fmt::Display for ProveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProveError::Instance(e) => write!(f, "{}", e),
+            ProveError::BatchSize {
+                circuits,
+                instances,
+            } => write!(
+                f,
+                "batch has {} circuits but {} instance columns",
+                circuits, instances
+            ),
+            ProveError::Plonk(e) => write!(f, "prover error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProveError {}
+
+impl From<InstanceError> for ProveError {
+    fn from(error: InstanceError) -> Self {
+        ProveError::Instance(error)
+    }
+}
+
+impl From<Error> for ProveError {
+    fn from(error: Error) -> Self {
+        ProveError::Plonk(error)
+    }
+}
+
+#[derive(Debug)]
+pub enum VerifyError {
+    /// The public inputs do not match the instance layout of the circuit.
+    Instance(InstanceError),
+    /// The proof is well-formed but does not satisfy the circuit for the given public inputs.
+    InvalidProof,
+    /// The proof bytes could not be read back from the transcript.
+    Transcript(std::io::Error),
+    /// Any other error reported by the verifier.
+    Plonk(Error),
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::Instance(e) => write!(f, "{}", e),
+            VerifyError::InvalidProof => write!(f, "proof is invalid for the given public inputs"),
+            VerifyError::Transcript(e) => write!(f, "malformed proof: {}", e),
+            VerifyError::Plonk(e) => write!(f, "verifier error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<InstanceError> for VerifyError {
+    fn from(error: InstanceError) -> Self {
+        VerifyError::Instance(error)
+    }
+}
+
+impl From<Error> for VerifyError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::ConstraintSystemFailure | Error::Opening => VerifyError::InvalidProof,
+            Error::Transcript(e) => VerifyError::Transcript(e),
+            e => VerifyError::Plonk(e),
+        }
+    }
+}
+
+/// Generates the proving key for `MyCircuit` in the given `mode`. In `ConstantMode::Public`
+/// the key does not depend on `constant`.
+pub fn keygen(
+    params: &Params<EqAffine>,
+    mode: ConstantMode,
+    constant: Fp,
+) -> Result<ProvingKey<EqAffine>, Error> {
+    let empty_circuit = MyCircuit::empty(mode, constant);
+
+    let vk = keygen_vk(params, &empty_circuit)?;
+    keygen_pk(params, vk, &empty_circuit)
+}
+
+/// Generates params of the smallest size that fits `MyCircuit` in the given `mode`,
+/// together with the proving key.
+pub fn setup(
+    mode: ConstantMode,
+    constant: Fp,
+) -> Result<(Params<EqAffine>, ProvingKey<EqAffine>), Error> {
+    let params = Params::new(MyCircuit::<Fp>::min_k(mode)?);
+    let pk = keygen(&params, mode, constant)?;
+    Ok((params, pk))
+}
+
+/// Creates a proof that `circuit` is satisfied with `public_inputs` as its instance column.
+///
+/// `public_inputs` must be laid out as `MyPublicInputs`, which is checked before proving.
+pub fn prove(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: MyCircuit<Fp>,
+    public_inputs: &[Fp],
+) -> Result<Proof, ProveError> {
+    prove_many(params, pk, &[circuit], &[public_inputs])
+}
+
+/// Creates a single proof that every circuit in `circuits` is satisfied, with
+/// `public_inputs[i]` as the instance column of `circuits[i]`.
+///
+/// The circuits share one transcript and one multiopening argument, so the proof is
+/// smaller and cheaper to verify than one proof per circuit.
+pub fn prove_many<I: AsRef<[Fp]>>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuits: &[MyCircuit<Fp>],
+    public_inputs: &[I],
+) -> Result<Proof, ProveError> {
+    if circuits.len() != public_inputs.len() {
+        return Err(ProveError::BatchSize {
+            circuits: circuits.len(),
+            instances: public_inputs.len(),
+        });
+    }
+    let instances = instances(public_inputs)?;
+    let instances: Vec<_> = instances.iter().map(|columns| &columns[..]).collect();
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof(params, pk, circuits, &instances, OsRng, &mut transcript)?;
+
+    Ok(Proof(transcript.finalize()))
+}
+
+/// Checks `proof` against `vk` and the expected `public_inputs`.
+pub fn verify(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &Proof,
+    public_inputs: &[Fp],
+) -> Result<(), VerifyError> {
+    verify_many(params, vk, proof, &[public_inputs])
+}
+
+/// Checks a proof from [`prove_many`] against `vk` and the expected instance column of
+/// each circuit, in the order they were proven.
+pub fn verify_many<I: AsRef<[Fp]>>(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &Proof,
+    public_inputs: &[I],
+) -> Result<(), VerifyError> {
+    let instances = instances(public_inputs)?;
+    let instances: Vec<_> = instances.iter().map(|columns| &columns[..]).collect();
+
+    let strategy = SingleVerifier::new(params);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof.as_bytes());
+    verify_proof(params, vk, strategy, &instances, &mut transcript)?;
+
+    Ok(())
+}
+
+/// Checks many independent proofs against `vk`, each with its own `public_inputs`.
+///
+/// The proofs are checked together with `BatchVerifier`, which folds their final MSMs
+/// into one. If the batch fails, every proof is verified again on its own and the
+/// invalid ones are returned with their index in `proofs`.
+pub fn verify_batch<I: AsRef<[Fp]>>(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proofs: &[(Proof, I)],
+) -> Result<(), Vec<(usize, VerifyError)>> {
+    let mut failures = vec![];
+    let mut batch = BatchVerifier::new();
+    for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+        let public_inputs = public_inputs.as_ref();
+        match MyPublicInputs::<Fp>::check(public_inputs) {
+            Ok(()) => batch.add_proof(vec![vec![public_inputs.to_vec()]], proof.0.clone()),
+            Err(e) => failures.push((i, e.into())),
+        }
+    }
+    if failures.is_empty() && batch.finalize(params, vk) {
+        return Ok(());
+    }
+
+    for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+        let public_inputs = public_inputs.as_ref();
+        if MyPublicInputs::<Fp>::check(public_inputs).is_ok() {
+            if let Err(e) = verify(params, vk, proof, public_inputs) {
+                failures.push((i, e));
+            }
+        }
+    }
+    failures.sort_by_key(|(i, _)| *i);
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+/// Checks each instance column against `MyPublicInputs` and wraps it as the only
+/// instance column of its circuit.
+fn instances<I: AsRef<[Fp]>>(public_inputs: &[I]) -> Result<Vec<[&[Fp]; 1]>, InstanceError> {
+    public_inputs
+        .iter()
+        .map(|public_inputs| {
+            let public_inputs = public_inputs.as_ref();
+            MyPublicInputs::<Fp>::check(public_inputs)?;
+            Ok([public_inputs])
+        })
+        .collect()
+}
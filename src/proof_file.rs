@@ -0,0 +1,166 @@
+use halo2_proofs::pasta::{group::ff::PrimeField, Fp};
+use std::fmt;
+
+use crate::proof::Proof;
+
+/// Identifies a proof file written by [`ProofFile::to_bytes`].
+const MAGIC: [u8; 8] = *b"h2hi-prf";
+
+/// Version of the proof file layout.
+pub const PROOF_FILE_VERSION: u16 = 1;
+
+/// The transcript a proof was created with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptKind {
+    /// `Blake2bWrite`/`Blake2bRead` with `Challenge255`.
+    Blake2b = 1,
+}
+
+impl TranscriptKind {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(TranscriptKind::Blake2b),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofFileError {
+    /// The file ended before all fields could be read.
+    Truncated,
+    /// The file does not start with the proof file magic bytes.
+    BadMagic,
+    /// The file was written with an unsupported format version.
+    UnsupportedVersion(u16),
+    /// The proof was created with an unknown transcript.
+    UnsupportedTranscript(u8),
+    /// The proof was created for a different circuit.
+    WrongCircuit,
+    /// The public instance value at this index is not a canonical field element.
+    InvalidInstance(usize),
+    /// The file has data after the proof.
+    TrailingBytes,
+}
+
+impl fmt::Display for ProofFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofFileError::Truncated => write!(f, "proof file is truncated"),
+            ProofFileError::BadMagic => write!(f, "not a proof file"),
+            ProofFileError::UnsupportedVersion(v) => {
+                write!(f, "unsupported proof file version {}", v)
+            }
+            ProofFileError::UnsupportedTranscript(t) => {
+                write!(f, "unsupported transcript {}", t)
+            }
+            ProofFileError::WrongCircuit => write!(f, "proof was created for a different circuit"),
+            ProofFileError::InvalidInstance(i) => write!(f, "public input {} is invalid", i),
+            ProofFileError::TrailingBytes => write!(f, "unexpected data after the proof"),
+        }
+    }
+}
+
+impl std::error::Error for ProofFileError {}
+
+/// A proof together with the metadata needed to verify it.
+///
+/// Layout (integers are little-endian):
+///
+/// ```text
+/// magic "h2hi-prf" | version u16 | transcript u8 | circuit id [u8; 32] | k u32
+/// | instance count u32 | instances [u8; 32]* | proof length u32 | proof
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofFile {
+    pub transcript: TranscriptKind,
+    /// Fingerprint of the circuit's constraint system, see [`crate::circuit_fingerprint`].
+    pub circuit_id: [u8; 32],
+    pub k: u32,
+    pub instances: Vec<Fp>,
+    pub proof: Proof,
+}
+
+/// Reads fixed-size fields off the front of a byte slice.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProofFileError> {
+        if self.0.len() < len {
+            return Err(ProofFileError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ProofFileError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn u32(&mut self) -> Result<u32, ProofFileError> {
+        self.array().map(u32::from_le_bytes)
+    }
+}
+
+impl ProofFile {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&PROOF_FILE_VERSION.to_le_bytes());
+        bytes.push(self.transcript as u8);
+        bytes.extend_from_slice(&self.circuit_id);
+        bytes.extend_from_slice(&self.k.to_le_bytes());
+        bytes.extend_from_slice(&(self.instances.len() as u32).to_le_bytes());
+        for value in &self.instances {
+            bytes.extend_from_slice(&value.to_repr());
+        }
+        bytes.extend_from_slice(&(self.proof.as_bytes().len() as u32).to_le_bytes());
+        bytes.extend_from_slice(self.proof.as_bytes());
+        bytes
+    }
+
+    /// Parses a proof file, rejecting it unless it was created for `circuit_id`.
+    pub fn from_bytes(bytes: &[u8], circuit_id: &[u8; 32]) -> Result<Self, ProofFileError> {
+        let mut reader = Reader(bytes);
+
+        if reader.array::<8>()? != MAGIC {
+            return Err(ProofFileError::BadMagic);
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != PROOF_FILE_VERSION {
+            return Err(ProofFileError::UnsupportedVersion(version));
+        }
+        let [transcript] = reader.array()?;
+        let transcript = TranscriptKind::from_byte(transcript)
+            .ok_or(ProofFileError::UnsupportedTranscript(transcript))?;
+        if &reader.array::<32>()? != circuit_id {
+            return Err(ProofFileError::WrongCircuit);
+        }
+        let k = reader.u32()?;
+
+        let count = reader.u32()? as usize;
+        let instances = (0..count)
+            .map(|i| {
+                let repr = reader.array::<32>()?;
+                Option::from(Fp::from_repr(repr)).ok_or(ProofFileError::InvalidInstance(i))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let len = reader.u32()? as usize;
+        let proof = reader.take(len)?.to_vec().into();
+        if !reader.0.is_empty() {
+            return Err(ProofFileError::TrailingBytes);
+        }
+
+        Ok(ProofFile {
+            transcript,
+            circuit_id: *circuit_id,
+            k,
+            instances,
+            proof,
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    dev::CircuitGates,
+    plonk::{Any, Circuit, Column, ConstraintSystem, Fixed, Selector},
+};
+
+use crate::table::InspectError;
+
+/// A column by type and index.
+pub(crate) type ColumnId = (Any, usize);
+
+/// A custom gate as declared with `create_gate`.
+///
+/// Polynomials are written as by [`CircuitGates`], e.g. `(A0@0 * A1@0 - A0@1) * S0`.
+#[derive(Clone, Debug)]
+pub(crate) struct Gate {
+    pub(crate) name: String,
+    /// The constraints as `(name, polynomial)`; most are unnamed.
+    pub(crate) constraints: Vec<(String, String)>,
+    /// The indices of the selectors the gate queries.
+    pub(crate) selectors: Vec<usize>,
+}
+
+/// The constraint system of a circuit before selector compression, as configured by
+/// `Circuit::configure`.
+#[derive(Clone, Debug)]
+pub(crate) struct Shape {
+    pub(crate) advice_columns: usize,
+    pub(crate) fixed_columns: usize,
+    pub(crate) instance_columns: usize,
+    pub(crate) selectors: usize,
+    pub(crate) gates: Vec<Gate>,
+    /// The columns in the permutation argument, in column order.
+    pub(crate) permutation: Vec<ColumnId>,
+    /// The fixed columns enabled for constants, in column order.
+    pub(crate) constants: Vec<Column<Fixed>>,
+    pub(crate) degree: usize,
+    pub(crate) blinding_factors: usize,
+    pub(crate) columns: Columns,
+}
+
+impl Shape {
+    /// Reads the configured constraint system `cs` of `C` through public API only.
+    ///
+    /// halo2_proofs 0.2.0 keeps the contents of a `ConstraintSystem` crate-private, so:
+    /// the gates are read from [`CircuitGates`]; the columns and selectors are counted
+    /// by allocating one more on a copy of `cs`; and a column is in the permutation, or
+    /// enabled for constants, if enabling it again leaves `cs.pinned()` unchanged.
+    pub(crate) fn new<F: FieldExt, C: Circuit<F>>(
+        cs: &ConstraintSystem<F>,
+    ) -> Result<Self, InspectError> {
+        let columns = Columns::new(cs);
+        let unchanged = |change: &dyn Fn(&mut ConstraintSystem<F>)| {
+            let mut probe = cs.clone();
+            change(&mut probe);
+            format!("{:?}", probe.pinned()) == format!("{:?}", cs.pinned())
+        };
+
+        let permutation = columns
+            .all()
+            .filter(|column| unchanged(&|cs| cs.enable_equality(*column)))
+            .filter_map(|column| columns.column(column))
+            .collect();
+        let constants = columns
+            .fixed
+            .iter()
+            .copied()
+            .filter(|column| unchanged(&|cs| cs.enable_constant(*column)))
+            .collect();
+
+        Ok(Shape {
+            advice_columns: columns.advice.len(),
+            fixed_columns: columns.fixed.len(),
+            instance_columns: columns.instance.len(),
+            selectors: columns.selectors.len(),
+            gates: gates::<F, C>()?,
+            permutation,
+            constants,
+            degree: cs.degree(),
+            blinding_factors: cs.blinding_factors(),
+            columns,
+        })
+    }
+}
+
+/// The columns and selectors of a constraint system, by index.
+///
+/// halo2_proofs keeps the index of a column or selector private, but a constraint system
+/// numbers them in order, so a scratch one hands out columns and selectors equal to
+/// those of the circuit. This also gives the fixed columns to pass the floor planner for
+/// constants, since columns can't be built from an index outside halo2_proofs.
+#[derive(Clone, Debug)]
+pub(crate) struct Columns {
+    pub(crate) advice: Vec<Column<Any>>,
+    pub(crate) fixed: Vec<Column<Fixed>>,
+    pub(crate) instance: Vec<Column<Any>>,
+    /// Each selector as a simple and as a complex selector, which compare unequal.
+    pub(crate) selectors: Vec<(Selector, Selector)>,
+}
+
+impl Columns {
+    /// The columns and selectors of `cs`: as many as it takes for a scratch constraint
+    /// system to hand out the next one `cs` would.
+    fn new<F: FieldExt>(cs: &ConstraintSystem<F>) -> Self {
+        let mut next = cs.clone();
+        let (advice, fixed, instance, selector) = (
+            next.advice_column(),
+            next.fixed_column(),
+            next.instance_column(),
+            next.selector(),
+        );
+
+        let mut scratch = ConstraintSystem::<F>::default();
+        let advice = until(advice, || scratch.advice_column());
+        let fixed = until(fixed, || scratch.fixed_column());
+        let instance = until(instance, || scratch.instance_column());
+        let simple = until(selector, || scratch.selector());
+        let mut scratch = ConstraintSystem::<F>::default();
+        let complex = simple.iter().map(|_| scratch.complex_selector());
+
+        Columns {
+            advice: advice.into_iter().map(Column::from).collect(),
+            fixed,
+            instance: instance.into_iter().map(Column::from).collect(),
+            selectors: simple.iter().copied().zip(complex).collect(),
+        }
+    }
+
+    /// Every column, advice first, then fixed and instance.
+    pub(crate) fn all(&self) -> impl Iterator<Item = Column<Any>> + '_ {
+        self.advice
+            .iter()
+            .copied()
+            .chain(self.fixed.iter().map(|c| Column::from(*c)))
+            .chain(self.instance.iter().copied())
+    }
+
+    pub(crate) fn column(&self, column: Column<Any>) -> Option<ColumnId> {
+        let index = match column.column_type() {
+            Any::Advice => self.advice.iter().position(|c| *c == column),
+            Any::Fixed => self.fixed.iter().position(|c| Column::from(*c) == column),
+            Any::Instance => self.instance.iter().position(|c| *c == column),
+        }?;
+        Some((*column.column_type(), index))
+    }
+
+    pub(crate) fn selector(&self, selector: &Selector) -> Option<usize> {
+        self.selectors
+            .iter()
+            .position(|(simple, complex)| simple == selector || complex == selector)
+    }
+}
+
+/// Takes from `next` up to, but not including, the first item equal to `last`.
+fn until<T: PartialEq>(last: T, mut next: impl FnMut() -> T) -> Vec<T> {
+    let mut items = vec![];
+    loop {
+        let item = next();
+        if item == last {
+            return items;
+        }
+        items.push(item);
+    }
+}
+
+/// Reads the `Display` output of [`CircuitGates`] for `C`:
+///
+/// ```text
+/// gate:
+/// - polynomial
+/// - constraint name:
+///   polynomial
+/// Total gates: ..
+/// ```
+fn gates<F: FieldExt, C: Circuit<F>>() -> Result<Vec<Gate>, InspectError> {
+    let text = CircuitGates::collect::<F, C>().to_string();
+    let mut gates: Vec<Gate> = vec![];
+    let mut name = None;
+    for line in text.lines() {
+        let gate = gates.last_mut();
+        if line.starts_with("Total ") {
+            break;
+        } else if let Some(poly) = line.strip_prefix("  ") {
+            let gate = gate.ok_or_else(|| unexpected(line))?;
+            let name = name.take().ok_or_else(|| unexpected(line))?;
+            gate.constraints.push((name, poly.to_string()));
+        } else if let Some(constraint) = line.strip_prefix("- ") {
+            let gate = gate.ok_or_else(|| unexpected(line))?;
+            match constraint.strip_suffix(':') {
+                Some(constraint) => name = Some(constraint.to_string()),
+                None => gate
+                    .constraints
+                    .push((String::new(), constraint.to_string())),
+            }
+        } else if let Some(gate) = line.strip_suffix(':') {
+            gates.push(Gate {
+                name: gate.to_string(),
+                constraints: vec![],
+                selectors: vec![],
+            });
+        } else {
+            return Err(unexpected(line));
+        }
+    }
+
+    for gate in &mut gates {
+        for (_, poly) in &gate.constraints {
+            for token in tokens(poly) {
+                if let Token::Query('S', index, _) = token {
+                    if !gate.selectors.contains(&index) {
+                        gate.selectors.push(index);
+                    }
+                }
+            }
+        }
+    }
+    Ok(gates)
+}
+
+fn unexpected(line: &str) -> InspectError {
+    InspectError::ConstraintSystem(format!("unexpected line `{}` in the gates", line))
+}
+
+/// A piece of a polynomial written by [`CircuitGates`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// A selector `S0`, or a query of an advice, fixed or instance column at a rotation,
+    /// e.g. `A0@-1`.
+    Query(char, usize, i32),
+    /// A constant written in hex, e.g. `0x2`.
+    Hex(&'a str),
+    /// Anything else: operators, parentheses and decimal constants.
+    Text(&'a str),
+}
+
+/// Splits a polynomial written by [`CircuitGates`] into tokens.
+pub(crate) fn tokens(poly: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = poly;
+    while let Some(c) = rest.chars().next() {
+        let word = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '@' || c == '-'))
+            .unwrap_or(rest.len());
+        let (token, len) = match (c, &rest[..word]) {
+            ('0', hex) if hex.starts_with("0x") => (Token::Hex(hex), word),
+            ('S' | 'A' | 'F' | 'I', query) => match query[1..].split_once('@') {
+                Some((index, rotation)) => match (index.parse(), rotation.parse()) {
+                    (Ok(index), Ok(rotation)) => (Token::Query(c, index, rotation), word),
+                    _ => (Token::Text(query), word),
+                },
+                None => match query[1..].parse() {
+                    Ok(index) if c == 'S' => (Token::Query(c, index, 0), word),
+                    _ => (Token::Text(query), word),
+                },
+            },
+            _ => (Token::Text(&rest[..c.len_utf8()]), c.len_utf8()),
+        };
+        tokens.push(token);
+        rest = &rest[len..];
+    }
+    tokens
+}
@@ -0,0 +1,204 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use halo2_proofs::{
+    circuit::Layouter,
+    dev::CircuitCost,
+    pasta::{Eq, Fp},
+    plonk::{Any, Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    shape::{tokens, Shape, Token},
+    table::{InspectError, Table},
+};
+
+/// The size and cost of a circuit at a given `k`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub k: u32,
+    /// The number of regions assigned by the circuit.
+    pub regions: usize,
+    /// One past the last row used in any column, including the constants and the
+    /// instance.
+    pub rows_used: usize,
+    /// The rows available to the circuit: `2^k` less the blinding rows.
+    pub usable_rows: usize,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    /// The maximum degree of the gates, lookups and permutation argument, with selectors
+    /// counted as degree 1.
+    pub max_degree: usize,
+    pub permutation_columns: usize,
+    pub lookups: usize,
+    /// The size of a proof of one instance of the circuit, in bytes.
+    pub proof_size: usize,
+    /// An estimate of the number of points in the final multiscalar multiplication of
+    /// the verifier. Selectors are counted as fixed columns, so this is an upper bound
+    /// when halo2_proofs manages to combine them.
+    pub verifier_msm_size: usize,
+}
+
+impl CircuitStats {
+    /// Synthesizes `circuit` with `instance` and measures it at `k`.
+    ///
+    /// The circuit does not need a witness. This fails like `MockProver::run` if the
+    /// circuit does not fit in `2^k` rows.
+    pub fn measure<C: Circuit<Fp>>(
+        k: u32,
+        circuit: &C,
+        instance: &[Vec<Fp>],
+    ) -> Result<Self, InspectError> {
+        let table = Table::record(k, circuit, instance)?;
+        let shape = &table.shape;
+        let cost = CircuitCost::<Eq, Measured<C>>::measure(k as usize, &Measured(circuit));
+        let lookups = cost_count(&cost, "lookups").ok_or_else(|| {
+            InspectError::ConstraintSystem("CircuitCost has no lookup count".to_string())
+        })?;
+
+        // Permutation products are committed in chunks of `max_degree - 2` columns.
+        let chunk = shape.degree - 2;
+        let permutation_products = shape.permutation.len().div_ceil(chunk);
+        // The verifier's final MSM takes the `2^k` generators of the inner product
+        // argument, plus every commitment it opens: the fixed columns and selectors
+        // (before compression), the permutation columns and products, the advice
+        // columns, three per lookup, the vanishing argument, the multiopen `f`, the
+        // `s` polynomial, and the `2k` rounds of the inner product argument.
+        let verifier_msm_size = (1 << k)
+            + shape.fixed_columns
+            + shape.selectors
+            + shape.permutation.len()
+            + permutation_products
+            + shape.advice_columns
+            + 3 * lookups
+            + shape.degree
+            + 1
+            + 1
+            + 2 * k as usize;
+
+        Ok(CircuitStats {
+            k,
+            regions: table.regions.len(),
+            rows_used: table.rows_used,
+            usable_rows: table.usable_rows(),
+            advice_columns: shape.advice_columns,
+            fixed_columns: shape.fixed_columns,
+            instance_columns: shape.instance_columns,
+            selectors: shape.selectors,
+            max_degree: shape.degree,
+            permutation_columns: shape.permutation.len(),
+            lookups,
+            proof_size: usize::from(cost.proof_size(1)) - 32 * phantom_point_sets(shape, lookups),
+            verifier_msm_size,
+        })
+    }
+}
+
+/// A circuit passed through to `CircuitCost`, whose `Debug` needs the circuit's.
+struct Measured<'a, C>(&'a C);
+
+impl<C> fmt::Debug for Measured<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Measured")
+    }
+}
+
+impl<C: Circuit<Fp>> Circuit<Fp> for Measured<'_, C> {
+    type Config = C::Config;
+
+    type FloorPlanner = C::FloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Measured(self.0)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        self.0.synthesize(config, layouter)
+    }
+}
+
+/// The count `name` of `cost`. `CircuitCost` keeps its counts private; its derived
+/// `Debug` prints them as `name: n`.
+/// The point sets `CircuitCost` counts for a lookup argument the circuit does not have.
+///
+/// halo2_proofs 0.2.0 adds the point sets of the lookup polynomials whether or not there
+/// are lookups, each costing one 32-byte evaluation. Without lookups, those that no
+/// column is queried at are not in a real proof.
+fn phantom_point_sets(shape: &Shape, lookups: usize) -> usize {
+    if lookups > 0 {
+        return 0;
+    }
+    let mut rotations: BTreeMap<(char, usize), BTreeSet<i32>> = BTreeMap::new();
+    for (_, poly) in shape.gates.iter().flat_map(|gate| &gate.constraints) {
+        for token in tokens(poly) {
+            if let Token::Query(prefix @ ('A' | 'F' | 'I'), index, rotation) = token {
+                rotations
+                    .entry((prefix, index))
+                    .or_default()
+                    .insert(rotation);
+            }
+        }
+    }
+    for (column_type, index) in &shape.permutation {
+        let prefix = match column_type {
+            Any::Advice => 'A',
+            Any::Fixed => 'F',
+            Any::Instance => 'I',
+        };
+        rotations.entry((prefix, *index)).or_default().insert(0);
+    }
+    let mut point_sets: BTreeSet<Vec<i32>> = rotations
+        .into_values()
+        .map(|rotations| rotations.into_iter().collect())
+        .collect();
+    // Selectors are compressed into fixed columns queried at the current row.
+    if shape.gates.iter().any(|gate| !gate.selectors.is_empty()) {
+        point_sets.insert(vec![0]);
+    }
+    [vec![-1, 0], vec![0]]
+        .iter()
+        .filter(|set| !point_sets.contains(*set))
+        .count()
+}
+
+fn cost_count(cost: &impl fmt::Debug, name: &str) -> Option<usize> {
+    let cost = format!("{:?}", cost);
+    let (_, count) = cost.split_once(&format!(" {}: ", name))?;
+    count
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "k:                   {}", self.k)?;
+        writeln!(f, "regions:             {}", self.regions)?;
+        writeln!(
+            f,
+            "rows:                {} used of {} ({} usable)",
+            self.rows_used,
+            1u64 << self.k,
+            self.usable_rows
+        )?;
+        writeln!(
+            f,
+            "columns:             {} advice, {} fixed, {} instance, {} selectors",
+            self.advice_columns, self.fixed_columns, self.instance_columns, self.selectors
+        )?;
+        writeln!(f, "max degree:          {}", self.max_degree)?;
+        writeln!(f, "permutation columns: {}", self.permutation_columns)?;
+        writeln!(f, "lookups:             {}", self.lookups)?;
+        writeln!(f, "proof size:          ~{} bytes", self.proof_size)?;
+        write!(f, "verifier MSM size:   ~{}", self.verifier_msm_size)
+    }
+}
@@ -0,0 +1,346 @@
+use std::{cell::Cell as Flag, fmt};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Value,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ColumnType, ConstraintSystem, Error,
+        Fixed, FloorPlanner, Instance, Selector,
+    },
+};
+
+use crate::shape::{ColumnId, Shape};
+
+/// Why a circuit could not be inspected by [`CircuitStats`](crate::CircuitStats),
+/// [`Trace`](crate::Trace) or [`WitnessTable`](crate::WitnessTable).
+#[derive(Debug)]
+pub enum InspectError {
+    /// Synthesis failed, e.g. because the circuit does not fit in `2^k` rows.
+    Synthesis(Error),
+    /// The constraint system could not be read: the gates listed by
+    /// `dev::CircuitGates` are read as printed by halo2_proofs 0.2.0.
+    ConstraintSystem(String),
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InspectError::Synthesis(e) => write!(f, "synthesis failed: {}", e),
+            InspectError::ConstraintSystem(e) => {
+                write!(f, "cannot read the constraint system: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InspectError {}
+
+impl From<Error> for InspectError {
+    fn from(error: Error) -> Self {
+        InspectError::Synthesis(error)
+    }
+}
+
+/// A cell assigned inside a region.
+#[derive(Clone, Debug)]
+pub(crate) struct Cell {
+    pub(crate) column: ColumnId,
+    pub(crate) row: usize,
+    pub(crate) annotation: String,
+}
+
+/// A region entered with `assign_region`.
+#[derive(Clone, Debug)]
+pub(crate) struct Region {
+    pub(crate) name: String,
+    /// The namespaces the region was assigned in, outermost first.
+    pub(crate) namespace: Vec<String>,
+    /// The first and last row the region uses, if it uses any.
+    pub(crate) rows: Option<(usize, usize)>,
+    pub(crate) cells: Vec<Cell>,
+    /// The selectors enabled in the region, by index and row.
+    pub(crate) selectors: Vec<(usize, usize)>,
+}
+
+impl Region {
+    fn touch(&mut self, row: usize) {
+        self.rows = Some(match self.rows {
+            Some((start, end)) => (start.min(row), end.max(row)),
+            None => (row, row),
+        });
+    }
+}
+
+/// The assignment table of a circuit, recorded by running its floor planner.
+///
+/// Unlike `MockProver`, the recorder keeps region names and cell annotations, and does
+/// not need a witness: unknown values are recorded as `None`.
+#[derive(Clone, Debug)]
+pub(crate) struct Table<F> {
+    pub(crate) k: u32,
+    pub(crate) shape: Shape,
+    pub(crate) regions: Vec<Region>,
+    pub(crate) advice: Vec<Vec<Option<F>>>,
+    pub(crate) fixed: Vec<Vec<Option<F>>>,
+    pub(crate) selectors: Vec<Vec<bool>>,
+    pub(crate) instance: Vec<Vec<F>>,
+    /// The cells assigned outside any region, which is where the floor planner puts
+    /// constants.
+    pub(crate) constants: Vec<Cell>,
+    /// The equality constraints, as pairs of cells.
+    pub(crate) copies: Vec<((ColumnId, usize), (ColumnId, usize))>,
+    /// One past the last row used in any column.
+    pub(crate) rows_used: usize,
+    /// Set when the floor planner hands over a column or selector that is not in
+    /// `shape.columns`, which fails synthesis.
+    unknown: Flag<bool>,
+    namespace: Vec<String>,
+    current: Option<Region>,
+}
+
+impl<F: FieldExt> Table<F> {
+    /// Synthesizes `circuit` with `instance` into a table of `2^k` rows.
+    ///
+    /// Like `MockProver::run`, this fails with `NotEnoughRowsAvailable` if the circuit
+    /// reaches into the blinding rows, and with `InstanceTooLarge` if an instance column
+    /// does.
+    pub(crate) fn record<C: Circuit<F>>(
+        k: u32,
+        circuit: &C,
+        instance: &[Vec<F>],
+    ) -> Result<Self, InspectError> {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let shape = Shape::new::<F, C>(&cs)?;
+
+        let n = 1 << k;
+        if n < cs.minimum_rows() {
+            return Err(Error::NotEnoughRowsAvailable { current_k: k }.into());
+        }
+        if instance.len() != shape.instance_columns {
+            return Err(Error::InvalidInstances.into());
+        }
+        let usable_rows = n - (shape.blinding_factors + 1);
+        if instance.iter().any(|column| column.len() > usable_rows) {
+            return Err(Error::InstanceTooLarge.into());
+        }
+
+        let mut table = Table {
+            k,
+            regions: vec![],
+            advice: vec![vec![None; n]; shape.advice_columns],
+            fixed: vec![vec![None; n]; shape.fixed_columns],
+            selectors: vec![vec![false; n]; shape.selectors],
+            instance: instance
+                .iter()
+                .map(|column| {
+                    let mut column = column.clone();
+                    column.resize(n, F::zero());
+                    column
+                })
+                .collect(),
+            constants: vec![],
+            copies: vec![],
+            rows_used: instance.iter().map(Vec::len).max().unwrap_or(0),
+            unknown: Flag::new(false),
+            namespace: vec![],
+            current: None,
+            shape,
+        };
+        let constants = table.shape.constants.clone();
+        match C::FloorPlanner::synthesize(&mut table, circuit, config, constants) {
+            Err(_) if table.unknown.get() => Err(InspectError::ConstraintSystem(
+                "the circuit uses a column or selector the constraint system does not list"
+                    .to_string(),
+            )),
+            result => result.map(|()| table).map_err(InspectError::Synthesis),
+        }
+    }
+
+    /// The rows available to the circuit, before the blinding rows.
+    pub(crate) fn usable_rows(&self) -> usize {
+        (1 << self.k) - (self.shape.blinding_factors + 1)
+    }
+
+    fn check_row(&mut self, row: usize) -> Result<(), Error> {
+        if row >= self.usable_rows() {
+            return Err(Error::NotEnoughRowsAvailable { current_k: self.k });
+        }
+        self.rows_used = self.rows_used.max(row + 1);
+        if let Some(region) = &mut self.current {
+            region.touch(row);
+        }
+        Ok(())
+    }
+
+    /// The type and index of `column`.
+    fn id<C: ColumnType>(&self, column: Column<C>) -> Result<ColumnId, Error>
+    where
+        Column<C>: Into<Column<Any>>,
+    {
+        self.shape.columns.column(column.into()).ok_or_else(|| {
+            self.unknown.set(true);
+            Error::Synthesis
+        })
+    }
+
+    /// The index of `selector`.
+    fn selector_id(&self, selector: &Selector) -> Result<usize, Error> {
+        self.shape.columns.selector(selector).ok_or_else(|| {
+            self.unknown.set(true);
+            Error::Synthesis
+        })
+    }
+
+    fn annotate(&mut self, column: ColumnId, row: usize, annotation: String) {
+        let cell = Cell {
+            column,
+            row,
+            annotation,
+        };
+        match &mut self.current {
+            Some(region) => region.cells.push(cell),
+            None => self.constants.push(cell),
+        }
+    }
+}
+
+impl<F: FieldExt> Assignment<F> for Table<F> {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        assert!(self.current.is_none(), "already in a region");
+        self.current = Some(Region {
+            name: name_fn().into(),
+            namespace: self.namespace.clone(),
+            rows: None,
+            cells: vec![],
+            selectors: vec![],
+        });
+    }
+
+    fn exit_region(&mut self) {
+        let region = self.current.take().expect("not in a region");
+        self.regions.push(region);
+    }
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _annotation: A,
+        selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        let index = self.selector_id(selector)?;
+        self.selectors[index][row] = true;
+        if let Some(region) = &mut self.current {
+            region.selectors.push((index, row));
+        }
+        Ok(())
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        if row >= self.usable_rows() {
+            return Err(Error::NotEnoughRowsAvailable { current_k: self.k });
+        }
+        let (_, index) = self.id(column)?;
+        Ok(Value::known(self.instance[index][row]))
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        let column = self.id(column)?;
+        self.advice[column.1][row] = known(to().into_field().evaluate());
+        self.annotate(column, row, annotation().into());
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        let column = self.id(column)?;
+        self.fixed[column.1][row] = known(to().into_field().evaluate());
+        self.annotate(column, row, annotation().into());
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        if left_row >= self.usable_rows() || right_row >= self.usable_rows() {
+            return Err(Error::NotEnoughRowsAvailable { current_k: self.k });
+        }
+        let (left, right) = (self.id(left_column)?, self.id(right_column)?);
+        self.copies.push(((left, left_row), (right, right_row)));
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        to: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        let usable_rows = self.usable_rows();
+        if row >= usable_rows {
+            return Err(Error::NotEnoughRowsAvailable { current_k: self.k });
+        }
+        let value = known(to.evaluate());
+        let (_, index) = self.id(column)?;
+        for cell in &mut self.fixed[index][row..usable_rows] {
+            *cell = value;
+        }
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespace.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {
+        self.namespace.pop();
+    }
+}
+
+fn known<F: Copy>(value: Value<F>) -> Option<F> {
+    let mut known = None;
+    value.map(|v| known = Some(v));
+    known
+}
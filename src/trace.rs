@@ -0,0 +1,511 @@
+use std::fmt;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{Any, Circuit},
+};
+
+use crate::{
+    expr::{fmt_field, parse_field, small},
+    shape::{tokens, ColumnId, Token},
+    table::{Cell, InspectError, Table},
+};
+
+/// A readable account of a circuit: its gates and permutation, and the regions and cells
+/// assigned by one run of its floor planner.
+///
+/// Columns are named `a0`, `a1`, ... for advice, `f0`, ... for fixed and `i0`, ... for
+/// instance columns, and a query at another row is written with its rotation, e.g.
+/// `a0[+1]`. A selector is named after the first gate that uses it, e.g. `s_mul`, or
+/// `s_lookup` if only a lookup uses it, and `s3` if the name is taken. A cell is written
+/// `a0@12`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub k: u32,
+    pub usable_rows: usize,
+    pub advice_columns: Vec<String>,
+    pub fixed_columns: Vec<String>,
+    pub instance_columns: Vec<String>,
+    pub selectors: Vec<String>,
+    pub gates: Vec<TraceGate>,
+    /// The columns in the permutation argument.
+    pub permutation: Vec<String>,
+    pub regions: Vec<TraceRegion>,
+    /// The cells assigned outside any region, such as the constants.
+    pub constants: Vec<TraceCell>,
+    /// The equality constraints between cells.
+    pub copies: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceGate {
+    pub name: String,
+    /// The constraints of the gate as `(name, polynomial)`; most are unnamed.
+    pub constraints: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceRegion {
+    pub name: String,
+    /// The namespaces the region was assigned in, outermost first.
+    pub namespace: Vec<String>,
+    /// The first and last row of the region, if it uses any.
+    pub rows: Option<(usize, usize)>,
+    pub cells: Vec<TraceCell>,
+    /// The selectors enabled in the region, by name and row.
+    pub selectors: Vec<(String, usize)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceCell {
+    /// The cell, e.g. `a0@12`.
+    pub cell: String,
+    pub annotation: String,
+    /// The assigned value, or `None` if the circuit has no witness.
+    pub value: Option<String>,
+}
+
+impl Trace {
+    /// Synthesizes `circuit` with `instance` into `2^k` rows and traces it.
+    ///
+    /// The circuit does not need a witness; the values are then left out.
+    pub fn new<F: FieldExt, C: Circuit<F>>(
+        k: u32,
+        circuit: &C,
+        instance: &[Vec<F>],
+    ) -> Result<Self, InspectError> {
+        let table = Table::record(k, circuit, instance)?;
+        let shape = &table.shape;
+        let selectors = selector_names(&table);
+        let rename = |poly: &String| rename::<F>(poly, &selectors);
+
+        let cell = |cell: &Cell| {
+            let (column_type, index) = cell.column;
+            let value = match column_type {
+                Any::Advice => table.advice[index][cell.row],
+                Any::Fixed => table.fixed[index][cell.row],
+                Any::Instance => Some(table.instance[index][cell.row]),
+            };
+            TraceCell {
+                cell: cell_name(cell.column, cell.row),
+                annotation: cell.annotation.clone(),
+                value: value.map(field),
+            }
+        };
+
+        Ok(Trace {
+            k,
+            usable_rows: table.usable_rows(),
+            advice_columns: (0..shape.advice_columns)
+                .map(|i| column_name((Any::Advice, i)))
+                .collect(),
+            fixed_columns: (0..shape.fixed_columns)
+                .map(|i| column_name((Any::Fixed, i)))
+                .collect(),
+            instance_columns: (0..shape.instance_columns)
+                .map(|i| column_name((Any::Instance, i)))
+                .collect(),
+            gates: shape
+                .gates
+                .iter()
+                .map(|gate| TraceGate {
+                    name: gate.name.clone(),
+                    constraints: gate
+                        .constraints
+                        .iter()
+                        .map(|(name, poly)| (name.clone(), rename(poly)))
+                        .collect(),
+                })
+                .collect(),
+            permutation: shape.permutation.iter().copied().map(column_name).collect(),
+            regions: table
+                .regions
+                .iter()
+                .map(|region| TraceRegion {
+                    name: region.name.clone(),
+                    namespace: region.namespace.clone(),
+                    rows: region.rows,
+                    cells: region.cells.iter().map(cell).collect(),
+                    selectors: region
+                        .selectors
+                        .iter()
+                        .map(|&(selector, row)| (selectors[selector].clone(), row))
+                        .collect(),
+                })
+                .collect(),
+            constants: table.constants.iter().map(cell).collect(),
+            copies: table
+                .copies
+                .iter()
+                .map(|&((left, left_row), (right, right_row))| {
+                    (cell_name(left, left_row), cell_name(right, right_row))
+                })
+                .collect(),
+            selectors,
+        })
+    }
+
+    /// The trace as pretty-printed JSON, with one cell per line so that traces can be
+    /// diffed.
+    pub fn to_json(&self) -> String {
+        let strings = |items: &[String]| Json::Array(items.iter().map(Json::string).collect());
+        let cells = |cells: &[TraceCell]| {
+            Json::Array(
+                cells
+                    .iter()
+                    .map(|cell| {
+                        Json::Object(vec![
+                            ("cell", Json::string(&cell.cell)),
+                            ("annotation", Json::string(&cell.annotation)),
+                            (
+                                "value",
+                                cell.value.as_ref().map_or(Json::Null, Json::string),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            )
+        };
+
+        let json = Json::Object(vec![
+            ("k", Json::Number(self.k as usize)),
+            ("usable_rows", Json::Number(self.usable_rows)),
+            (
+                "columns",
+                Json::Object(vec![
+                    ("advice", strings(&self.advice_columns)),
+                    ("fixed", strings(&self.fixed_columns)),
+                    ("instance", strings(&self.instance_columns)),
+                    ("selectors", strings(&self.selectors)),
+                ]),
+            ),
+            (
+                "gates",
+                Json::Array(
+                    self.gates
+                        .iter()
+                        .map(|gate| {
+                            let constraints = gate
+                                .constraints
+                                .iter()
+                                .map(|(name, poly)| {
+                                    Json::Object(vec![
+                                        ("name", Json::string(name)),
+                                        ("poly", Json::string(poly)),
+                                    ])
+                                })
+                                .collect();
+                            Json::Object(vec![
+                                ("name", Json::string(&gate.name)),
+                                ("constraints", Json::Array(constraints)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            ("permutation", strings(&self.permutation)),
+            (
+                "regions",
+                Json::Array(
+                    self.regions
+                        .iter()
+                        .map(|region| {
+                            let rows = region.rows.map_or(Json::Null, |(start, end)| {
+                                Json::Array(vec![Json::Number(start), Json::Number(end)])
+                            });
+                            Json::Object(vec![
+                                ("name", Json::string(&region.name)),
+                                ("namespace", strings(&region.namespace)),
+                                ("rows", rows),
+                                ("cells", cells(&region.cells)),
+                                (
+                                    "selectors",
+                                    Json::Array(
+                                        region
+                                            .selectors
+                                            .iter()
+                                            .map(|(selector, row)| {
+                                                Json::string(format!("{}@{}", selector, row))
+                                            })
+                                            .collect(),
+                                    ),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            ("constants", cells(&self.constants)),
+            (
+                "copies",
+                Json::Array(
+                    self.copies
+                        .iter()
+                        .map(|(left, right)| {
+                            Json::Array(vec![Json::string(left), Json::string(right)])
+                        })
+                        .collect(),
+                ),
+            ),
+        ]);
+
+        let mut out = String::new();
+        json.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "k = {} ({} usable rows)", self.k, self.usable_rows)?;
+        writeln!(f, "advice:    {}", self.advice_columns.join(", "))?;
+        writeln!(f, "fixed:     {}", self.fixed_columns.join(", "))?;
+        writeln!(f, "instance:  {}", self.instance_columns.join(", "))?;
+        writeln!(f, "selectors: {}", self.selectors.join(", "))?;
+
+        writeln!(f, "\ngates:")?;
+        for gate in &self.gates {
+            writeln!(f, "  {}:", gate.name)?;
+            for (name, poly) in &gate.constraints {
+                if name.is_empty() {
+                    writeln!(f, "    {}", poly)?;
+                } else {
+                    writeln!(f, "    {}: {}", name, poly)?;
+                }
+            }
+        }
+
+        writeln!(f, "\npermutation: {}", self.permutation.join(", "))?;
+
+        writeln!(f, "\nregions:")?;
+        for (i, region) in self.regions.iter().enumerate() {
+            let mut name = region.namespace.clone();
+            name.push(region.name.clone());
+            match region.rows {
+                Some((start, end)) => {
+                    writeln!(f, "  #{} {}: rows {}..={}", i, name.join(" / "), start, end)?
+                }
+                None => writeln!(f, "  #{} {}: no rows", i, name.join(" / "))?,
+            }
+            // Runs of rows are written `s_mul@4..=7`.
+            let mut runs: Vec<(&str, usize, usize)> = vec![];
+            for (selector, row) in &region.selectors {
+                match runs.last_mut() {
+                    Some((last, _, end)) if last == selector && *end + 1 == *row => *end = *row,
+                    _ => runs.push((selector, *row, *row)),
+                }
+            }
+            let runs: Vec<_> = runs
+                .into_iter()
+                .map(|(selector, start, end)| {
+                    if start == end {
+                        format!("{}@{}", selector, start)
+                    } else {
+                        format!("{}@{}..={}", selector, start, end)
+                    }
+                })
+                .collect();
+            if !runs.is_empty() {
+                writeln!(f, "    enabled {}", runs.join(", "))?;
+            }
+            for cell in &region.cells {
+                writeln!(f, "    {}", cell)?;
+            }
+        }
+
+        if !self.constants.is_empty() {
+            writeln!(f, "\nconstants:")?;
+            for cell in &self.constants {
+                writeln!(f, "  {}", cell)?;
+            }
+        }
+
+        if !self.copies.is_empty() {
+            writeln!(f, "\ncopies:")?;
+            for (left, right) in &self.copies {
+                writeln!(f, "  {} == {}", left, right)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TraceCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} = {}",
+            self.cell,
+            self.value.as_deref().unwrap_or("?")
+        )?;
+        if !self.annotation.is_empty() {
+            write!(f, "  ({})", self.annotation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Names each selector after the first gate that queries it. A selector no gate queries
+/// can only be used by a lookup, so it is named `s_lookup`.
+pub(crate) fn selector_names<F>(table: &Table<F>) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for selector in 0..table.shape.selectors {
+        let gate = table
+            .shape
+            .gates
+            .iter()
+            .find(|gate| gate.selectors.contains(&selector));
+        let name: String = gate
+            .map_or("lookup", |gate| &gate.name)
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | '0'..='9' => c,
+                'A'..='Z' => c.to_ascii_lowercase(),
+                _ => '_',
+            })
+            .collect();
+        let name = format!("s_{}", name);
+        if names.contains(&name) {
+            names.push(format!("s{}", selector));
+        } else {
+            names.push(name);
+        }
+    }
+    names
+}
+
+pub(crate) fn column_name((column_type, index): ColumnId) -> String {
+    let prefix = match column_type {
+        Any::Advice => "a",
+        Any::Fixed => "f",
+        Any::Instance => "i",
+    };
+    format!("{}{}", prefix, index)
+}
+
+fn cell_name(column: ColumnId, row: usize) -> String {
+    format!("{}@{}", column_name(column), row)
+}
+
+/// A field element in decimal if it or its negation is small, and in hex otherwise.
+pub(crate) fn field<F: FieldExt>(value: F) -> String {
+    match small(&-value) {
+        Some(n) if small(&value).is_none() => format!("-{}", n),
+        _ => Field(value).to_string(),
+    }
+}
+
+/// Writes a field element the way an [`Expr`](crate::Expr) constant is written.
+struct Field<F>(F);
+
+impl<F: FieldExt> fmt::Display for Field<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_field(&self.0, f)
+    }
+}
+
+/// Names the columns and selectors of a polynomial written by `CircuitGates`, e.g.
+/// `(A0@0 * A1@0 - A0@1) * S0` as `(a0 * a1 - a0[+1]) * s_mul`, and writes constants
+/// as [`field`] does.
+fn rename<F: FieldExt>(poly: &str, selectors: &[String]) -> String {
+    tokens(poly)
+        .into_iter()
+        .map(|token| match token {
+            Token::Query('S', index, _) => selectors
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| format!("s{}", index)),
+            Token::Query(prefix, index, rotation) => {
+                let column_type = match prefix {
+                    'A' => Any::Advice,
+                    'F' => Any::Fixed,
+                    _ => Any::Instance,
+                };
+                match rotation {
+                    0 => column_name((column_type, index)),
+                    _ => format!("{}[{:+}]", column_name((column_type, index)), rotation),
+                }
+            }
+            Token::Hex(hex) => parse_field::<F>(hex).map_or(hex.to_string(), field),
+            Token::Text(text) => text.to_string(),
+        })
+        .collect()
+}
+
+/// Just enough JSON to write a trace.
+enum Json {
+    Null,
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn string(s: impl Into<String>) -> Self {
+        Json::String(s.into())
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Json::Array(_) | Json::Object(_))
+    }
+
+    /// Writes arrays and objects of scalars on one line, and anything else one item per
+    /// line.
+    fn write(&self, out: &mut String, indent: usize) {
+        let (open, close, items): (char, char, Vec<(Option<&str>, &Json)>) = match self {
+            Json::Null => return out.push_str("null"),
+            Json::Number(n) => return out.push_str(&n.to_string()),
+            Json::String(s) => return write_string(out, s),
+            Json::Array(items) => ('[', ']', items.iter().map(|item| (None, item)).collect()),
+            Json::Object(fields) => (
+                '{',
+                '}',
+                fields
+                    .iter()
+                    .map(|(key, value)| (Some(*key), value))
+                    .collect(),
+            ),
+        };
+
+        let inline = items.iter().all(|(_, item)| item.is_scalar());
+        out.push(open);
+        for (i, (key, item)) in items.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+                if inline {
+                    out.push(' ');
+                }
+            }
+            if !inline {
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent + 1));
+            }
+            if let Some(key) = key {
+                write_string(out, key);
+                out.push_str(": ");
+            }
+            item.write(out, indent + 1);
+        }
+        if !inline && !items.is_empty() {
+            out.push('\n');
+            out.push_str(&"  ".repeat(indent));
+        }
+        out.push(close);
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,225 @@
+use halo2_proofs::{
+    pasta::{group::ff::PrimeField, EqAffine, Fp},
+    plonk::{keygen_vk, Circuit, ConstraintSystem, Error, VerifyingKey},
+    poly::commitment::Params,
+};
+use std::{fmt, fs, io, path::Path};
+
+use crate::{
+    circuit::{ConstantMode, MyCircuit},
+    params::params_k,
+};
+
+/// Identifies a verifying key file written by [`VkArtifact::write`].
+const MAGIC: [u8; 8] = *b"h2hi-vk\0";
+
+/// Version of the verifying key file layout.
+const VERSION: u16 = 2;
+
+#[derive(Debug)]
+pub enum VkError {
+    /// The verifying key could not be read or written.
+    Io(io::Error),
+    /// The file does not start with the verifying key magic bytes.
+    BadMagic,
+    /// The file was written with an unsupported format version.
+    UnsupportedVersion(u16),
+    /// The stored constant mode is not one of `ConstantMode`.
+    InvalidMode(u8),
+    /// The stored fixed constant is not a canonical field element.
+    InvalidConstant,
+    /// The key was exported for a circuit with a different constraint system.
+    CircuitMismatch,
+    /// The key was exported for params with a different `k`.
+    WrongK { expected: u32, found: u32 },
+    /// Regenerating the key from the params does not reproduce the exported key.
+    KeyMismatch,
+    /// Key generation failed.
+    Plonk(Error),
+}
+
+impl fmt::Display for VkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VkError::Io(e) => write!(f, "{}", e),
+            VkError::BadMagic => write!(f, "not a verifying key file"),
+            VkError::UnsupportedVersion(v) => {
+                write!(f, "unsupported verifying key version {}", v)
+            }
+            VkError::InvalidMode(mode) => {
+                write!(f, "verifying key holds an invalid constant mode {}", mode)
+            }
+            VkError::InvalidConstant => write!(f, "verifying key holds an invalid constant"),
+            VkError::CircuitMismatch => {
+                write!(f, "verifying key was exported for a different circuit")
+            }
+            VkError::WrongK { expected, found } => write!(
+                f,
+                "verifying key was exported for k = {}, but the params have k = {}",
+                found, expected
+            ),
+            VkError::KeyMismatch => write!(f, "verifying key does not match the params"),
+            VkError::Plonk(e) => write!(f, "keygen failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VkError {}
+
+impl From<io::Error> for VkError {
+    fn from(error: io::Error) -> Self {
+        VkError::Io(error)
+    }
+}
+
+impl From<Error> for VkError {
+    fn from(error: Error) -> Self {
+        VkError::Plonk(error)
+    }
+}
+
+fn hash(personal: &[u8], bytes: &[u8]) -> [u8; 32] {
+    let digest = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(personal)
+        .hash(bytes);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(digest.as_bytes());
+    out
+}
+
+/// Hashes the constraint system of `C`: its gates, selectors, column layout,
+/// permutation and lookups.
+pub fn circuit_fingerprint<C: Circuit<Fp>>() -> [u8; 32] {
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+    hash(b"halo2_hi-cs", format!("{:?}", cs.pinned()).as_bytes())
+}
+
+/// Hashes the pinned representation of `vk`, the same data halo2 commits to in transcripts.
+fn vk_digest(vk: &VerifyingKey<EqAffine>) -> [u8; 32] {
+    hash(b"halo2_hi-vk", format!("{:?}", vk.pinned()).as_bytes())
+}
+
+/// An exported verifying key for `MyCircuit`.
+///
+/// halo2_proofs 0.2.0 cannot deserialize a `VerifyingKey`, so the artifact records
+/// everything needed to re-derive it (`k`, the constant mode and the fixed constant)
+/// together with fingerprints of the constraint system and of the key itself. Loading
+/// re-runs `keygen_vk` on a witness-free circuit and rejects any mismatch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VkArtifact {
+    pub k: u32,
+    pub mode: ConstantMode,
+    /// The fixed constant, zero in `ConstantMode::Public`.
+    pub constant: Fp,
+    pub fingerprint: [u8; 32],
+    pub digest: [u8; 32],
+}
+
+impl VkArtifact {
+    /// Generates the verifying key for `mode` and `constant` and records it.
+    pub fn generate(
+        params: &Params<EqAffine>,
+        mode: ConstantMode,
+        constant: Fp,
+    ) -> Result<Self, Error> {
+        let constant = match mode {
+            ConstantMode::Fixed => constant,
+            ConstantMode::Public => Fp::zero(),
+        };
+        let vk = keygen_vk(params, &MyCircuit::empty(mode, constant))?;
+
+        Ok(VkArtifact {
+            k: params_k(params),
+            mode,
+            constant,
+            fingerprint: circuit_fingerprint::<MyCircuit<Fp>>(),
+            digest: vk_digest(&vk),
+        })
+    }
+
+    /// Re-derives the verifying key from `params`, checking it against the artifact.
+    pub fn load(&self, params: &Params<EqAffine>) -> Result<VerifyingKey<EqAffine>, VkError> {
+        if self.fingerprint != circuit_fingerprint::<MyCircuit<Fp>>() {
+            return Err(VkError::CircuitMismatch);
+        }
+        let k = params_k(params);
+        if self.k != k {
+            return Err(VkError::WrongK {
+                expected: k,
+                found: self.k,
+            });
+        }
+
+        let vk = keygen_vk(params, &MyCircuit::empty(self.mode, self.constant))?;
+        if vk_digest(&vk) != self.digest {
+            return Err(VkError::KeyMismatch);
+        }
+        Ok(vk)
+    }
+
+    pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.k.to_le_bytes())?;
+        writer.write_all(&[self.mode as u8])?;
+        writer.write_all(&self.constant.to_repr())?;
+        writer.write_all(&self.fingerprint)?;
+        writer.write_all(&self.digest)
+    }
+
+    pub fn read<R: io::Read>(reader: &mut R) -> Result<Self, VkError> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(VkError::BadMagic);
+        }
+
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != VERSION {
+            return Err(VkError::UnsupportedVersion(version));
+        }
+
+        let mut k = [0u8; 4];
+        reader.read_exact(&mut k)?;
+
+        let mut mode = [0u8; 1];
+        reader.read_exact(&mut mode)?;
+        let mode = match mode[0] {
+            0 => ConstantMode::Fixed,
+            1 => ConstantMode::Public,
+            mode => return Err(VkError::InvalidMode(mode)),
+        };
+
+        let mut repr = [0u8; 32];
+        reader.read_exact(&mut repr)?;
+        let constant = Option::from(Fp::from_repr(repr)).ok_or(VkError::InvalidConstant)?;
+
+        let mut fingerprint = [0u8; 32];
+        reader.read_exact(&mut fingerprint)?;
+        let mut digest = [0u8; 32];
+        reader.read_exact(&mut digest)?;
+
+        Ok(VkArtifact {
+            k: u32::from_le_bytes(k),
+            mode,
+            constant,
+            fingerprint,
+            digest,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut bytes = vec![];
+        self.write(&mut bytes)?;
+        fs::write(path, bytes)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, VkError> {
+        let bytes = fs::read(path)?;
+        Self::read(&mut &bytes[..])
+    }
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use halo2_hi::{MyChip, MyConfig};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::{MockProver, VerifyFailure},
+    pasta::{group::ff::PrimeField, Fp},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+const K: u32 = 11;
+
+/// Decomposes `value` and exposes its bits, then the recomposed value.
+#[derive(Clone)]
+struct ToBits {
+    value: Value<Fp>,
+    num_bits: usize,
+}
+
+/// Recomposes private `bits` and exposes the result.
+#[derive(Clone)]
+struct FromBits {
+    bits: Vec<Value<Fp>>,
+}
+
+impl Circuit<Fp> for ToBits {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        ToBits {
+            value: Value::unknown(),
+            num_bits: self.num_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        common::configure(meta)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+
+        let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        let bits = chip.to_bits(layouter.namespace(|| "to bits"), value, self.num_bits)?;
+        let recomposed = chip.from_bits(layouter.namespace(|| "from bits"), &bits)?;
+
+        for (row, bit) in bits.into_iter().enumerate() {
+            chip.expose_public(
+                layouter.namespace(|| format!("expose bit {}", row)),
+                bit,
+                row,
+            )?;
+        }
+        chip.expose_public(
+            layouter.namespace(|| "expose recomposed"),
+            recomposed,
+            self.num_bits,
+        )
+    }
+}
+
+impl Circuit<Fp> for FromBits {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        FromBits {
+            bits: vec![Value::unknown(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        common::configure(meta)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+
+        let bits = self
+            .bits
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| {
+                chip.load_private(layouter.namespace(|| format!("load bit {}", i)), *bit)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = chip.from_bits(layouter.namespace(|| "from bits"), &bits)?;
+
+        chip.expose_public(layouter.namespace(|| "expose value"), value, 0)
+    }
+}
+
+/// The low `n` bits of a little-endian integer, least significant first.
+fn le_bits(bytes: &[u8], n: usize) -> Vec<bool> {
+    (0..n).map(|i| (bytes[i / 8] >> (i % 8)) & 1 == 1).collect()
+}
+
+fn to_field(bits: &[bool]) -> Vec<Fp> {
+    bits.iter().map(|b| Fp::from(*b as u64)).collect()
+}
+
+fn decompose(value: Fp, num_bits: usize) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = ToBits {
+        value: Value::known(value),
+        num_bits,
+    };
+    let mut instance = to_field(&le_bits(value.to_repr().as_ref(), num_bits));
+    instance.push(value);
+    MockProver::run(K, &circuit, vec![instance])
+        .unwrap()
+        .verify()
+}
+
+fn recompose(bits: &[bool], expected: Fp) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = FromBits {
+        bits: to_field(bits).into_iter().map(Value::known).collect(),
+    };
+    MockProver::run(K, &circuit, vec![vec![expected]])
+        .unwrap()
+        .verify()
+}
+
+/// The little-endian bytes of the integer `p + x`, which is below 2^255.
+fn p_plus(x: u8) -> [u8; 32] {
+    let mut bytes = (-Fp::one()).to_repr();
+    let mut carry = 1 + x as u16;
+    for byte in bytes.iter_mut() {
+        let sum = *byte as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+    assert_eq!(carry, 0);
+    bytes
+}
+
+#[test]
+fn small_values_round_trip() {
+    assert_eq!(decompose(Fp::from(13), 4), Ok(()));
+    assert_eq!(decompose(Fp::from(0), 1), Ok(()));
+    assert_eq!(decompose(Fp::from(u64::MAX), 64), Ok(()));
+    assert_eq!(recompose(&[true, false, true, true], Fp::from(13)), Ok(()));
+}
+
+#[test]
+fn bits_are_exposed_least_significant_first() {
+    let circuit = ToBits {
+        value: Value::known(Fp::from(6)),
+        num_bits: 3,
+    };
+    let wrong_order = vec![Fp::one(), Fp::one(), Fp::zero(), Fp::from(6)];
+    let prover = MockProver::run(K, &circuit, vec![wrong_order]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn values_wider_than_the_decomposition_fail() {
+    assert!(decompose(Fp::from(16), 4).is_err());
+    assert!(decompose(-Fp::one(), 254).is_err());
+}
+
+#[test]
+fn values_near_the_modulus_decompose() {
+    let bits = Fp::NUM_BITS as usize;
+    for value in [
+        -Fp::one(),
+        -Fp::from(2),
+        -Fp::from(1 << 20),
+        Fp::zero(),
+        Fp::one(),
+    ] {
+        assert_eq!(decompose(value, bits), Ok(()), "{:?}", value);
+    }
+    let two_254 = Fp::from(2).pow(&[254, 0, 0, 0]);
+    assert_eq!(decompose(two_254, bits), Ok(()));
+    assert_eq!(decompose(two_254 - Fp::one(), bits), Ok(()));
+}
+
+#[test]
+fn non_canonical_bits_are_rejected() {
+    let bits = Fp::NUM_BITS as usize;
+    let max = le_bits((-Fp::one()).to_repr().as_ref(), bits);
+    assert_eq!(recompose(&max, -Fp::one()), Ok(()));
+
+    // p and p + 1 fit in 255 bits but alias 0 and 1.
+    for x in [0, 1] {
+        let aliased = le_bits(&p_plus(x), bits);
+        assert!(recompose(&aliased, Fp::from(x as u64)).is_err());
+    }
+
+    // Below 255 bits there is nothing to alias.
+    assert_eq!(
+        recompose(
+            &max[..bits - 1],
+            -Fp::one() - Fp::from(2).pow(&[254, 0, 0, 0])
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn non_boolean_bits_are_rejected() {
+    let circuit = FromBits {
+        bits: vec![Value::known(Fp::from(2)), Value::known(Fp::zero())],
+    };
+    let failures = MockProver::run(K, &circuit, vec![vec![Fp::from(2)]])
+        .unwrap()
+        .verify()
+        .unwrap_err();
+    assert!(failures
+        .iter()
+        .all(|failure| failure.to_string().contains("('bits')")));
+}
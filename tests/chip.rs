@@ -0,0 +1,131 @@
+mod common;
+
+use common::{assert_gate_fails, Op, OpCircuit};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::Value,
+    dev::{MockProver, VerifyFailure},
+    pasta::Fp,
+    plonk::Error,
+};
+
+fn run(op: Op, a: Fp, b: Fp, out: Fp) -> Result<(), Vec<VerifyFailure>> {
+    common::run(4, op, [a, b, Fp::zero()], Some(out))
+}
+
+fn run_tampered(op: Op, a: Fp, b: Fp, out: Fp) -> Result<(), Vec<VerifyFailure>> {
+    common::run_tampered(4, op, a, b, out)
+}
+
+#[test]
+fn sub() {
+    let (a, b) = (Fp::from(5), Fp::from(7));
+    assert_eq!(run(Op::Sub, a, b, -Fp::from(2)), Ok(()));
+    assert_eq!(run(Op::Sub, b, a, Fp::from(2)), Ok(()));
+    assert!(run(Op::Sub, a, b, Fp::from(2)).is_err());
+}
+
+#[test]
+fn neg() {
+    assert_eq!(run(Op::Neg, Fp::from(5), Fp::zero(), -Fp::from(5)), Ok(()));
+    assert_eq!(run(Op::Neg, Fp::zero(), Fp::zero(), Fp::zero()), Ok(()));
+    assert!(run(Op::Neg, Fp::from(5), Fp::zero(), Fp::from(5)).is_err());
+}
+
+#[test]
+fn tampered_sub_output_is_rejected() {
+    let (a, b) = (Fp::from(5), Fp::from(7));
+    assert_eq!(run_tampered(Op::Sub, a, b, a - b), Ok(()));
+    assert_gate_fails(run_tampered(Op::Sub, a, b, b - a), "sub");
+    assert_gate_fails(run_tampered(Op::Sub, a, b, a + b), "sub");
+}
+
+#[test]
+fn tampered_neg_output_is_rejected() {
+    let a = Fp::from(5);
+    assert_eq!(run_tampered(Op::Neg, a, Fp::zero(), -a), Ok(()));
+    assert_gate_fails(run_tampered(Op::Neg, a, Fp::zero(), a), "neg");
+    assert_gate_fails(run_tampered(Op::Neg, a, Fp::zero(), Fp::zero()), "neg");
+}
+
+#[test]
+fn inv() {
+    let a = Fp::from(5);
+    assert_eq!(run(Op::Inv, a, Fp::zero(), a.invert().unwrap()), Ok(()));
+    assert_eq!(run(Op::Inv, Fp::one(), Fp::zero(), Fp::one()), Ok(()));
+    assert!(run(Op::Inv, a, Fp::zero(), a).is_err());
+}
+
+#[test]
+fn div() {
+    let (a, b) = (Fp::from(35), Fp::from(7));
+    assert_eq!(run(Op::Div, a, b, Fp::from(5)), Ok(()));
+    assert_eq!(run(Op::Div, b, a, b * a.invert().unwrap()), Ok(()));
+    assert_eq!(run(Op::Div, Fp::zero(), b, Fp::zero()), Ok(()));
+    assert!(run(Op::Div, a, b, Fp::from(6)).is_err());
+}
+
+#[test]
+fn is_zero() {
+    assert_eq!(run(Op::IsZero, Fp::zero(), Fp::zero(), Fp::one()), Ok(()));
+    assert_eq!(run(Op::IsZero, Fp::from(5), Fp::zero(), Fp::zero()), Ok(()));
+    assert_eq!(run(Op::IsZero, -Fp::one(), Fp::zero(), Fp::zero()), Ok(()));
+    assert!(run(Op::IsZero, Fp::zero(), Fp::zero(), Fp::zero()).is_err());
+    assert!(run(Op::IsZero, Fp::from(5), Fp::zero(), Fp::one()).is_err());
+}
+
+#[test]
+fn zero_denominator_is_a_synthesis_error() {
+    for (op, a, b) in [
+        (Op::Inv, Fp::zero(), Fp::zero()),
+        (Op::Div, Fp::from(5), Fp::zero()),
+    ] {
+        let circuit = OpCircuit {
+            op,
+            inputs: [a, b, Fp::zero()].map(Value::known),
+        };
+        assert!(matches!(
+            MockProver::run(4, &circuit, vec![vec![Fp::zero()]]),
+            Err(Error::Synthesis)
+        ));
+    }
+}
+
+#[test]
+fn tampered_inv_output_is_rejected() {
+    let a = Fp::from(5);
+    assert_eq!(
+        run_tampered(Op::Inv, a, Fp::zero(), a.invert().unwrap()),
+        Ok(())
+    );
+    assert_gate_fails(run_tampered(Op::Inv, a, Fp::zero(), a), "inv");
+    assert_gate_fails(
+        run_tampered(Op::Inv, Fp::zero(), Fp::zero(), Fp::zero()),
+        "inv",
+    );
+}
+
+#[test]
+fn tampered_div_output_is_rejected() {
+    let (a, b) = (Fp::from(35), Fp::from(7));
+    assert_eq!(run_tampered(Op::Div, a, b, Fp::from(5)), Ok(()));
+    assert_gate_fails(run_tampered(Op::Div, a, b, Fp::from(6)), "div");
+    // With a zero denominator there is no inverse to witness, whatever the output.
+    assert_gate_fails(
+        run_tampered(Op::Div, Fp::zero(), Fp::zero(), Fp::zero()),
+        "div",
+    );
+}
+
+#[test]
+fn tampered_is_zero_output_is_rejected() {
+    let a = Fp::from(5);
+    assert_gate_fails(
+        run_tampered(Op::IsZero, a, Fp::zero(), Fp::one()),
+        "is_zero",
+    );
+    assert_gate_fails(
+        run_tampered(Op::IsZero, Fp::zero(), Fp::zero(), Fp::zero()),
+        "is_zero",
+    );
+}
@@ -0,0 +1,64 @@
+use halo2_hi::{ConstantMode, MyCircuit};
+use halo2_proofs::{circuit::Value, dev::MockProver, pasta::Fp};
+
+fn circuit(constant: u64, a: u64, b: u64, c: u64) -> MyCircuit<Fp> {
+    MyCircuit {
+        constant: Fp::from(constant),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(a)),
+        b: Value::known(Fp::from(b)),
+        c: Value::known(Fp::from(c)),
+    }
+}
+
+#[test]
+fn satisfied_with_correct_output() {
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    assert_eq!(public[0], Fp::from(230));
+
+    let prover = MockProver::run(8, &circuit(2, 10, 5, 3), vec![public]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn unsatisfied_with_wrong_output() {
+    let mut public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    public[0] = Fp::from(231);
+
+    let prover = MockProver::run(8, &circuit(2, 10, 5, 3), vec![public]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn unsatisfied_with_wrong_commitment() {
+    let mut public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    public[1] += Fp::one();
+
+    let prover = MockProver::run(8, &circuit(2, 10, 5, 3), vec![public]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn public_constant_is_read_from_the_instance() {
+    for constant in [2, 3] {
+        let public =
+            MyCircuit::public_inputs(Fp::from(constant), Fp::from(10), Fp::from(5), Fp::from(3));
+        let circuit = MyCircuit {
+            mode: ConstantMode::Public,
+            ..circuit(0, 10, 5, 3)
+        };
+
+        let prover = MockProver::run(8, &circuit, vec![public]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
+
+#[test]
+fn fixed_constant_is_exposed() {
+    let mut public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    assert_eq!(public[2], Fp::from(2));
+    public[2] = Fp::from(3);
+
+    let prover = MockProver::run(8, &circuit(2, 10, 5, 3), vec![public]).unwrap();
+    assert!(prover.verify().is_err());
+}
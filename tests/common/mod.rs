@@ -0,0 +1,243 @@
+//! Circuits shared by the tests of `MyChip`'s operations.
+#![allow(dead_code)]
+
+use halo2_hi::{MyChip, MyConfig, Number};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::{MockProver, VerifyFailure},
+    pasta::Fp,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// The width of the operands of the comparisons.
+pub const BITS: usize = 16;
+
+/// Configures `MyChip` over two advice columns, an instance and a constant column.
+pub fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> MyConfig {
+    let advice = [meta.advice_column(), meta.advice_column()];
+    let instance = meta.instance_column();
+    let constant = meta.fixed_column();
+
+    MyChip::configure(meta, advice, instance, constant)
+}
+
+/// Configures `MyChip` as [`configure`] does, with the range table.
+pub fn configure_with_range<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> MyConfig {
+    let advice = [meta.advice_column(), meta.advice_column()];
+    let instance = meta.instance_column();
+    let constant = meta.fixed_column();
+
+    MyChip::configure_with_range(meta, advice, instance, constant)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Sub,
+    Neg,
+    Inv,
+    Div,
+    IsZero,
+    AssertBool,
+    And,
+    Or,
+    Xor,
+    Not,
+    Select,
+    Lt,
+    Le,
+    Eq,
+    IsEqual,
+    Min,
+    Max,
+}
+
+impl Op {
+    /// Whether the operation range checks its operands to [`BITS`] bits.
+    fn uses_range(self) -> bool {
+        matches!(self, Op::Lt | Op::Le | Op::Eq | Op::Min | Op::Max)
+    }
+}
+
+/// Applies `op` to the private `inputs` with the chip and exposes the result, if any.
+#[derive(Clone)]
+pub struct OpCircuit<F: FieldExt> {
+    pub op: Op,
+    pub inputs: [Value<F>; 3],
+}
+
+impl<F: FieldExt> Circuit<F> for OpCircuit<F> {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        OpCircuit {
+            op: self.op,
+            inputs: [Value::unknown(); 3],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure_with_range(meta)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+        if self.op.uses_range() {
+            chip.load_range_table(layouter.namespace(|| "range table"))?;
+        }
+
+        let [a, b, c] = self.inputs;
+        let a = chip.load_private(layouter.namespace(|| "load a"), a)?;
+        let b = chip.load_private(layouter.namespace(|| "load b"), b)?;
+        let c = chip.load_private(layouter.namespace(|| "load c"), c)?;
+        let op = layouter.namespace(|| format!("{:?}", self.op));
+        let out = match self.op {
+            Op::Sub => chip.sub(op, a, b)?,
+            Op::Neg => chip.neg(op, a)?,
+            Op::Inv => chip.inv(op, a)?,
+            Op::Div => chip.div(op, a, b)?,
+            Op::IsZero => chip.is_zero(op, a)?,
+            Op::AssertBool => return chip.assert_bool(op, a),
+            Op::And => chip.and(op, a, b)?,
+            Op::Or => chip.or(op, a, b)?,
+            Op::Xor => chip.xor(op, a, b)?,
+            Op::Not => chip.not(op, a)?,
+            Op::Select => chip.select(op, a, b, c)?,
+            Op::Lt => chip.lt(op, a, b, BITS)?,
+            Op::Le => chip.le(op, a, b, BITS)?,
+            Op::Eq => chip.eq(op, a, b, BITS)?,
+            Op::IsEqual => chip.is_equal(op, a, b)?,
+            Op::Min => chip.min(op, a, b, BITS)?,
+            Op::Max => chip.max(op, a, b, BITS)?,
+        };
+
+        chip.expose_public(layouter.namespace(|| "expose out"), out, 0)
+    }
+}
+
+/// Lays out the gate for `op` by hand, with `out` as the claimed result.
+///
+/// For `Op::Lt`, `out` is the claimed `lt` bit and the difference it implies is range
+/// checked as `MyChip::lt` does.
+#[derive(Clone)]
+pub struct TamperedCircuit<F: FieldExt> {
+    pub op: Op,
+    pub a: Value<F>,
+    pub b: Value<F>,
+    pub out: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for TamperedCircuit<F> {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        TamperedCircuit {
+            op: self.op,
+            a: Value::unknown(),
+            b: Value::unknown(),
+            out: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure_with_range(meta)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let low = layouter.assign_region(
+            || "tampered",
+            |mut region| {
+                region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                // The inverse witnesses are computed honestly; only `out` is tampered with.
+                let inv = |v: Value<F>| v.map(|v| v.invert().unwrap_or(F::zero()));
+                match self.op {
+                    Op::Sub => {
+                        config.s_sub.enable(&mut region, 0)?;
+                        region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+                    }
+                    Op::Neg => config.s_neg.enable(&mut region, 0)?,
+                    Op::Inv => {
+                        config.s_inv.enable(&mut region, 0)?;
+                        region.assign_advice(|| "out", config.advice[1], 0, || self.out)?;
+                        return Ok(None);
+                    }
+                    Op::Div => {
+                        config.s_div.enable(&mut region, 0)?;
+                        region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+                        region.assign_advice(|| "1 / b", config.advice[1], 1, || inv(self.b))?;
+                    }
+                    Op::IsZero => {
+                        config.s_is_zero.enable(&mut region, 0)?;
+                        region.assign_advice(|| "1 / a", config.advice[1], 0, || inv(self.a))?;
+                    }
+                    Op::Lt => {
+                        let pow2 = F::from(1 << BITS);
+                        config.range.as_ref().unwrap().s_lt.enable(&mut region, 0)?;
+                        region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+                        region.assign_advice(|| "lt", config.advice[1], 1, || self.out)?;
+                        region.assign_advice_from_constant(
+                            || "2^bits",
+                            config.advice[0],
+                            2,
+                            pow2,
+                        )?;
+
+                        let low = self.a.zip(self.b).zip(self.out);
+                        let low = low.map(|((a, b), lt)| a - b + lt * pow2);
+                        return region
+                            .assign_advice(|| "low", config.advice[0], 1, || low)
+                            .map(|low| Some(Number(low)));
+                    }
+                    op => panic!("{:?} has no tampered layout", op),
+                }
+                region.assign_advice(|| "out", config.advice[0], 1, || self.out)?;
+                Ok(None)
+            },
+        )?;
+
+        if let Some(low) = low {
+            let chip = MyChip::new(config);
+            chip.load_range_table(layouter.namespace(|| "range table"))?;
+            chip.range_check(layouter.namespace(|| "range check low"), low, BITS)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `op` on `inputs` at `k` and verifies it against the exposed `out`, if any.
+pub fn run(k: u32, op: Op, inputs: [Fp; 3], out: Option<Fp>) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = OpCircuit {
+        op,
+        inputs: inputs.map(Value::known),
+    };
+    MockProver::run(k, &circuit, vec![out.into_iter().collect()])
+        .unwrap()
+        .verify()
+}
+
+/// Runs the tampered layout of `op` at `k` and verifies it.
+pub fn run_tampered(k: u32, op: Op, a: Fp, b: Fp, out: Fp) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = TamperedCircuit {
+        op,
+        a: Value::known(a),
+        b: Value::known(b),
+        out: Value::known(out),
+    };
+    MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify()
+}
+
+/// Asserts that `result` failed, and only by constraints of `gate`.
+pub fn assert_gate_fails(result: Result<(), Vec<VerifyFailure>>, gate: &str) {
+    let failures = result.unwrap_err();
+    assert!(!failures.is_empty());
+    for failure in &failures {
+        assert!(
+            matches!(failure, VerifyFailure::ConstraintNotSatisfied { .. })
+                && failure.to_string().contains(&format!("('{}')", gate)),
+            "unexpected failure: {}",
+            failure
+        );
+    }
+}
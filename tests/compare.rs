@@ -0,0 +1,87 @@
+mod common;
+
+use common::{Op, BITS};
+use halo2_hi::RANGE_TABLE_BITS;
+use halo2_proofs::{dev::VerifyFailure, pasta::Fp};
+
+fn k() -> u32 {
+    RANGE_TABLE_BITS as u32 + 1
+}
+
+fn run(op: Op, a: u64, b: u64, out: u64) -> Result<(), Vec<VerifyFailure>> {
+    common::run(k(), op, [a, b, 0].map(Fp::from), Some(Fp::from(out)))
+}
+
+/// Checks that `op(a, b)` verifies with `out` exposed, and not with the other outcome.
+fn check(op: Op, a: u64, b: u64, out: u64) {
+    assert_eq!(
+        run(op, a, b, out),
+        Ok(()),
+        "{:?}({}, {}) = {}",
+        op,
+        a,
+        b,
+        out
+    );
+    let other = match op {
+        Op::Min | Op::Max => a + b - out,
+        _ => 1 - out,
+    };
+    if other != out {
+        assert!(
+            run(op, a, b, other).is_err(),
+            "{:?}({}, {}) = {}",
+            op,
+            a,
+            b,
+            other
+        );
+    }
+}
+
+const PAIRS: [(u64, u64); 6] = [(3, 5), (5, 3), (4, 4), (0, 0), (0, 65535), (65535, 65534)];
+
+#[test]
+fn lt_and_le() {
+    for (a, b) in PAIRS {
+        check(Op::Lt, a, b, (a < b) as u64);
+        check(Op::Le, a, b, (a <= b) as u64);
+    }
+}
+
+#[test]
+fn eq_and_is_equal() {
+    for (a, b) in PAIRS {
+        check(Op::Eq, a, b, (a == b) as u64);
+        check(Op::IsEqual, a, b, (a == b) as u64);
+    }
+}
+
+#[test]
+fn min_and_max() {
+    for (a, b) in [(3, 5), (5, 3), (4, 4), (0, 65535)] {
+        check(Op::Min, a, b, a.min(b));
+        check(Op::Max, a, b, a.max(b));
+    }
+}
+
+#[test]
+fn operands_out_of_range_fail() {
+    let failures = run(Op::Lt, 1 << BITS, 5, 0).unwrap_err();
+    assert!(failures
+        .iter()
+        .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+}
+
+#[test]
+fn wrong_lt_bit_is_rejected() {
+    let run = |a: u64, b: u64, lt: u64| {
+        common::run_tampered(k(), Op::Lt, Fp::from(a), Fp::from(b), Fp::from(lt))
+    };
+
+    assert_eq!(run(3, 5, 1), Ok(()));
+    assert_eq!(run(5, 3, 0), Ok(()));
+    assert!(run(3, 5, 0).is_err());
+    assert!(run(5, 3, 1).is_err());
+    assert!(run(5, 3, 2).is_err());
+}
@@ -0,0 +1,202 @@
+use halo2_hi::{EvalError, ParseError, Program};
+use halo2_proofs::{arithmetic::Field, dev::MockProver, pasta::Fp};
+use std::collections::BTreeMap;
+
+fn inputs(values: &[(&str, u64)]) -> BTreeMap<String, Fp> {
+    values
+        .iter()
+        .map(|(name, value)| (name.to_string(), Fp::from(*value)))
+        .collect()
+}
+
+fn parse(src: &str) -> Result<Program<Fp>, ParseError> {
+    Program::parse(src)
+}
+
+fn error_at(src: &str) -> (usize, usize, String) {
+    let e = parse(src).unwrap_err();
+    (e.line, e.column, e.message)
+}
+
+#[test]
+fn my_circuit_statement_is_satisfied() {
+    let program = parse(include_str!("../circuits/my_circuit.circ")).unwrap();
+    assert_eq!(program.inputs, ["a", "b", "c"]);
+    assert_eq!(program.public, ["d"]);
+    assert_eq!(program.assignments[0].1.to_string(), "2 * (a^2 + b * c)");
+
+    let values = inputs(&[("a", 10), ("b", 5), ("c", 3)]);
+    let public = program.evaluate(&values).unwrap();
+    assert_eq!(public, [Fp::from(230)]);
+
+    let prover = MockProver::run(5, &program.circuit(&values), vec![public]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let prover = MockProver::run(5, &program.circuit(&values), vec![vec![Fp::from(231)]]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn multiple_statements_and_outputs() {
+    let program = parse(
+        "private x, y;
+         s = x + y;      // sum
+         p = x * y;
+         q = (s + 1)^3 * p^0;
+         public s, q;
+         public p;",
+    )
+    .unwrap();
+
+    let values = inputs(&[("x", 3), ("y", 4)]);
+    let public = program.evaluate(&values).unwrap();
+    assert_eq!(public, [Fp::from(7), Fp::from(512), Fp::from(12)]);
+
+    let prover = MockProver::run(6, &program.circuit(&values), vec![public]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn subtraction_and_unary_minus() {
+    let program =
+        parse("private a, b, c; d = a^2 - b*c; e = -a^2 * -(b - c) - -1; public d, e;").unwrap();
+    assert_eq!(program.assignments[0].1.to_string(), "a^2 - b * c");
+    assert_eq!(
+        program.assignments[1].1.to_string(),
+        "-(a^2) * -(b - c) - -1"
+    );
+
+    let values = inputs(&[("a", 3), ("b", 5), ("c", 7)]);
+    let public = program.evaluate(&values).unwrap();
+    assert_eq!(public, [-Fp::from(26), -Fp::from(17)]);
+
+    let prover = MockProver::run(6, &program.circuit(&values), vec![public]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn nested_powers_lay_out_their_base_once() {
+    let program = parse("private a; d = (((a + 1)^64)^64)^63; public d;").unwrap();
+    assert_eq!(program.assignments[0].1.to_string(), "(((a + 1)^64)^64)^63");
+
+    let values = inputs(&[("a", 2)]);
+    let public = program.evaluate(&values).unwrap();
+    assert_eq!(public, [Fp::from(3).pow_vartime([64 * 64 * 63])]);
+
+    // 6 + 6 + 10 multiplications fit in 2^6 rows; expanding the powers would not.
+    let prover = MockProver::run(6, &program.circuit(&values), vec![public]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn missing_witness_values_are_reported() {
+    let program = parse("private x, y; z = x * y; public z;").unwrap();
+    assert_eq!(
+        program.evaluate(&inputs(&[("x", 3)])),
+        Err(EvalError::UnknownVariable("y".to_string()))
+    );
+}
+
+#[test]
+fn syntax_errors_have_positions() {
+    assert_eq!(
+        error_at("private a;\nd = a +;\n"),
+        (2, 8, "expected an expression, found `;`".to_string())
+    );
+    assert_eq!(
+        error_at("private a\nd = a;"),
+        (2, 1, "expected `;`, found `d`".to_string())
+    );
+    assert_eq!(
+        error_at("private a;\n  d = a ^ a;"),
+        (2, 11, "expected an exponent, found `a`".to_string())
+    );
+    assert!(parse("private a;\nd = a^64;\npublic d;").is_ok());
+    assert_eq!(
+        error_at("private a;\nd = a^4000000000;"),
+        (2, 7, "exponent `4000000000` is larger than 64".to_string())
+    );
+    assert_eq!(
+        error_at("private a;\nd = a^99999999999999999999;"),
+        (
+            2,
+            7,
+            "expected an exponent, found `99999999999999999999`".to_string()
+        )
+    );
+    assert_eq!(
+        error_at("private a;\nd = a % 1;"),
+        (2, 7, "unexpected character `%`".to_string())
+    );
+    assert_eq!(
+        error_at("private a;\nd = (a;"),
+        (2, 7, "expected `)`, found `;`".to_string())
+    );
+}
+
+#[test]
+fn undeclared_variables_are_rejected() {
+    assert_eq!(
+        error_at("private a;\nd = a * b;\npublic d;"),
+        (2, 9, "undeclared variable `b`".to_string())
+    );
+    assert_eq!(
+        error_at("private a;\npublic d;"),
+        (2, 8, "undeclared variable `d`".to_string())
+    );
+    // Values must be defined before they are used.
+    assert_eq!(
+        error_at("private a;\nd = e;\ne = a;"),
+        (2, 5, "undeclared variable `e`".to_string())
+    );
+}
+
+#[test]
+fn redefinitions_are_rejected() {
+    assert_eq!(
+        error_at("private a, a;"),
+        (1, 12, "`a` is already defined".to_string())
+    );
+    assert_eq!(
+        error_at("private a;\na = a * a;"),
+        (2, 1, "`a` is already defined".to_string())
+    );
+}
+
+#[test]
+fn division_and_comments() {
+    let program = parse("private x, y; // inputs\nz = x / y / 2; // quotient\npublic z;").unwrap();
+    assert_eq!(program.assignments[0].1.to_string(), "x / y / 2");
+
+    let values = inputs(&[("x", 12), ("y", 3)]);
+    let public = program.evaluate(&values).unwrap();
+    assert_eq!(public, [Fp::from(2)]);
+
+    let prover = MockProver::run(6, &program.circuit(&values), vec![public]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    assert_eq!(
+        program.evaluate(&inputs(&[("x", 12), ("y", 0)])),
+        Err(EvalError::DivisionByZero)
+    );
+}
+
+/// The modulus of `Fp`, in decimal.
+const MODULUS: &str = "28948022309329048855892746252171976963363056481941\
+                       560715954676764349967630337";
+
+#[test]
+fn constants_must_be_below_the_modulus() {
+    let below = MODULUS.replace("0337", "0336");
+    let program = parse(&format!("private x; y = x + {}; public y;", below)).unwrap();
+    assert_eq!(program.evaluate(&inputs(&[("x", 1)])), Ok(vec![Fp::zero()]));
+
+    assert_eq!(
+        error_at(&format!("private x;\ny = x + {};", MODULUS)),
+        (
+            2,
+            9,
+            format!("`{}` is not below the field modulus", MODULUS)
+        )
+    );
+}
@@ -0,0 +1,101 @@
+use halo2_hi::{CircuitStats, ConstantMode, MyCircuit, WitnessTable};
+use halo2_proofs::{circuit::Value, pasta::Fp};
+
+fn table() -> WitnessTable {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    WitnessTable::new(k, &circuit(), &[public]).unwrap()
+}
+
+fn circuit() -> MyCircuit<Fp> {
+    MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(10)),
+        b: Value::known(Fp::from(5)),
+        c: Value::known(Fp::from(3)),
+    }
+}
+
+/// The cell of `table` in `row` under `header`.
+fn cell<'a>(table: &'a WitnessTable, row: usize, header: &str) -> &'a str {
+    let column = table.headers.iter().position(|h| h == header).unwrap();
+    &table.rows[row][column]
+}
+
+#[test]
+fn table_has_every_column_by_row() {
+    let table = table();
+    assert_eq!(&table.headers[..3], ["row", "f0", "f1"]);
+    assert!(table.headers.iter().any(|h| h == "s_mul"));
+    assert_eq!(
+        &table.headers[table.headers.len() - 4..],
+        ["a4", "i0", "region", "annotations"]
+    );
+    assert!(table
+        .rows
+        .iter()
+        .all(|row| row.len() == table.headers.len()));
+
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    let stats = CircuitStats::measure(k, &circuit(), &[public]).unwrap();
+    assert_eq!(table.rows.len(), stats.rows_used);
+}
+
+#[test]
+fn table_has_values_regions_and_annotations() {
+    let table = table();
+    assert_eq!(cell(&table, 0, "row"), "0");
+    assert_eq!(cell(&table, 0, "a0"), "10");
+    assert_eq!(cell(&table, 0, "i0"), "230");
+    assert_eq!(cell(&table, 0, "f0"), "2");
+    assert!(cell(&table, 0, "region").contains("load a / load private"));
+    assert!(cell(&table, 0, "annotations").contains("a0: private input"));
+
+    assert_eq!(cell(&table, 4, "s_mul"), "1");
+    assert_eq!(cell(&table, 5, "s_mul"), "0");
+    assert_eq!(cell(&table, 5, "a0"), "100");
+    // Unassigned cells are empty.
+    assert_eq!(cell(&table, 5, "a1"), "");
+}
+
+#[test]
+fn table_without_a_witness_has_no_advice_values() {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let circuit = MyCircuit::empty(ConstantMode::Fixed, Fp::from(2));
+    let table = WitnessTable::new(k, &circuit, &[vec![Fp::zero(); 3]]).unwrap();
+    assert_eq!(cell(&table, 0, "a0"), "");
+    assert_eq!(cell(&table, 0, "f0"), "2");
+    assert_eq!(table.rows.len(), self::table().rows.len());
+}
+
+#[test]
+fn csv_has_a_line_per_row() {
+    let mut csv = vec![];
+    table().write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+
+    assert!(lines.next().unwrap().starts_with("row,f0,f1,"));
+    let first = lines.next().unwrap();
+    assert!(first.starts_with("0,2,"));
+    assert!(first
+        .contains(",load a / load private; H / block 0 / absorb and permute,a0: private input; "));
+    assert_eq!(csv.lines().count(), table().rows.len() + 1);
+}
+
+#[cfg(feature = "xlsx")]
+#[test]
+fn xlsx_is_a_reproducible_zip() {
+    let mut xlsx = vec![];
+    table().write_xlsx(&mut xlsx).unwrap();
+    let mut again = vec![];
+    table().write_xlsx(&mut again).unwrap();
+
+    assert_eq!(xlsx, again);
+    assert!(xlsx.starts_with(b"PK\x03\x04"));
+    let text = String::from_utf8_lossy(&xlsx);
+    assert!(text.contains("xl/worksheets/sheet1.xml"));
+    assert!(text.contains("<c r=\"A1\" t=\"inlineStr\"><is><t>row</t></is></c>"));
+}
@@ -0,0 +1,163 @@
+use halo2_hi::{parse_field, EvalError, Expr, ExprCircuit, MyCircuit};
+use halo2_proofs::{arithmetic::Field, dev::MockProver, pasta::Fp};
+use std::collections::BTreeMap;
+
+fn inputs(values: &[(&str, u64)]) -> BTreeMap<String, Fp> {
+    values
+        .iter()
+        .map(|(name, value)| (name.to_string(), Fp::from(*value)))
+        .collect()
+}
+
+fn c(value: u64) -> Expr<Fp> {
+    Expr::constant(Fp::from(value))
+}
+
+/// Checks the circuit for `expr` against its native evaluation.
+fn check(expr: Expr<Fp>, inputs: &BTreeMap<String, Fp>) {
+    let expected = expr.evaluate(inputs).unwrap();
+    let circuit = ExprCircuit::new(expr, inputs);
+
+    let prover = MockProver::run(6, &circuit, vec![vec![expected]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let prover = MockProver::run(6, &circuit, vec![vec![expected + Fp::one()]]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn my_circuit_is_an_expression() {
+    let expr = MyCircuit::expr(Fp::from(2));
+    assert_eq!(expr.to_string(), "2 * (a * a + b * c)");
+    assert_eq!(expr.variables(), ["a", "b", "c"]);
+
+    let values = inputs(&[("a", 10), ("b", 5), ("c", 3)]);
+    assert_eq!(expr.evaluate(&values), Ok(Fp::from(230)));
+    check(expr, &values);
+}
+
+#[test]
+fn expressions_match_native_evaluation() {
+    let (x, y, z) = (Expr::var("x"), Expr::var("y"), Expr::var("z"));
+    let values = inputs(&[("x", 7), ("y", 11), ("z", 13)]);
+
+    check(x.clone(), &values);
+    check(c(42), &values);
+    check(x.clone() + y.clone(), &values);
+    check(x.clone() * y.clone() * z.clone() + c(7), &values);
+    check((x.clone() + c(1)) * (y.clone() + c(2)) * z.clone(), &values);
+    check(
+        x.clone() * x.clone() * x.clone() + z * c(3) + y * x,
+        &values,
+    );
+}
+
+#[test]
+fn subtraction_and_negation() {
+    let (a, b, c) = (Expr::var("a"), Expr::var("b"), Expr::var("c"));
+    let values = inputs(&[("a", 3), ("b", 5), ("c", 7)]);
+
+    let expr = a.clone() * a.clone() - b.clone() * c.clone();
+    assert_eq!(expr.to_string(), "a * a - b * c");
+    assert_eq!(expr.evaluate(&values), Ok(-Fp::from(26)));
+    check(expr, &values);
+
+    let expr = -(a.clone() - (b.clone() - c.clone())) * -c;
+    assert_eq!(expr.to_string(), "-(a - (b - c)) * -c");
+    assert_eq!(expr.evaluate(&values), Ok(Fp::from(35)));
+    check(expr, &values);
+
+    check(-a.clone() + b - -a, &values);
+}
+
+#[test]
+fn powers() {
+    let x = Expr::var("x");
+    let values = inputs(&[("x", 3)]);
+
+    for n in [0, 1, 2, 5, 8, 13] {
+        let expr = (x.clone() + c(1)).pow(n);
+        assert_eq!(
+            expr.evaluate(&values),
+            Ok(Fp::from(4).pow_vartime([n as u64]))
+        );
+        check(expr, &values);
+    }
+    assert_eq!((x.clone() + c(1)).pow(3).to_string(), "(x + 1)^3");
+    assert_eq!((-x.pow(2)).to_string(), "-(x^2)");
+}
+
+#[test]
+fn large_values_wrap_around_the_modulus() {
+    let x = Expr::var("x");
+    let values: BTreeMap<_, _> = [("x".to_string(), -Fp::one())].into_iter().collect();
+
+    let expr = x.clone() * x + c(1);
+    assert_eq!(expr.evaluate(&values), Ok(Fp::from(2)));
+    check(expr, &values);
+}
+
+#[test]
+fn missing_inputs_fail_synthesis() {
+    let expr = Expr::var("x") + Expr::var("y");
+    let circuit = ExprCircuit::new(expr.clone(), &inputs(&[("x", 1)]));
+
+    assert_eq!(
+        expr.evaluate(&inputs(&[("x", 1)])),
+        Err(EvalError::UnknownVariable("y".to_string()))
+    );
+    assert!(MockProver::run(6, &circuit, vec![vec![Fp::one()]]).is_err());
+}
+
+#[test]
+fn division() {
+    let expr = (Expr::var("x") + c(1)) / (Expr::var("y") * c(2));
+    assert_eq!(expr.to_string(), "(x + 1) / (y * 2)");
+
+    let values = inputs(&[("x", 11), ("y", 3)]);
+    assert_eq!(expr.evaluate(&values), Ok(Fp::from(2)));
+    check(expr.clone(), &values);
+
+    let values = inputs(&[("x", 1), ("y", 3)]);
+    assert_eq!(
+        expr.evaluate(&values),
+        Ok(Fp::from(2) * Fp::from(6).invert().unwrap())
+    );
+    check(expr, &values);
+}
+
+#[test]
+fn division_by_zero_is_an_error() {
+    let expr = Expr::var("x") / (Expr::var("y") - c(3));
+    let values = inputs(&[("x", 1), ("y", 3)]);
+
+    assert_eq!(expr.evaluate(&values), Err(EvalError::DivisionByZero));
+    let circuit = ExprCircuit::new(expr, &values);
+    assert!(MockProver::run(6, &circuit, vec![vec![Fp::zero()]]).is_err());
+}
+
+/// The modulus of `Fp`, in decimal and in hex.
+const MODULUS: &str = "28948022309329048855892746252171976963363056481941\
+                       560715954676764349967630337";
+const MODULUS_HEX: &str = "0x40000000000000000000000000000000224698fc094cf91b992d30ed00000001";
+
+#[test]
+fn field_elements_are_parsed_below_the_modulus() {
+    let parse = parse_field::<Fp>;
+    assert_eq!(parse("230"), Some(Fp::from(230)));
+    assert_eq!(parse("0xe6"), Some(Fp::from(230)));
+    assert_eq!(parse(&MODULUS.replace("0337", "0336")), Some(-Fp::one()));
+    assert_eq!(
+        parse(&MODULUS_HEX.replace("0001", "0000")),
+        Some(-Fp::one())
+    );
+
+    // The modulus and p + 1 are rejected in decimal as in hex.
+    assert_eq!(parse(MODULUS), None);
+    assert_eq!(parse(&MODULUS.replace("0337", "0338")), None);
+    assert_eq!(parse(MODULUS_HEX), None);
+    assert_eq!(parse(&"9".repeat(100)), None);
+    for invalid in ["", "0x", "12a", "-1", "0xg"] {
+        assert_eq!(parse(invalid), None, "{}", invalid);
+    }
+}
@@ -0,0 +1,165 @@
+use halo2_hi::{
+    keygen, prove, verify, ConstantMode, InstanceError, MyChip, MyCircuit, MyConfig,
+    MyPublicInputs, ProveError, PublicInputs, VerifyError,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    pasta::{EqAffine, Fp},
+    plonk::{Circuit, ConstraintSystem, Error},
+    poly::commitment::Params,
+};
+
+/// Public inputs `x` and `y` with public outputs `x * y` and `x + y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Io<F> {
+    x: F,
+    y: F,
+    product: F,
+    sum: F,
+}
+
+impl<F: FieldExt> PublicInputs<F> for Io<F> {
+    const NAMES: &'static [&'static str] = &["x", "y", "product", "sum"];
+
+    fn to_instance(&self) -> Vec<F> {
+        vec![self.x, self.y, self.product, self.sum]
+    }
+
+    fn from_instance(instance: &[F]) -> Result<Self, InstanceError> {
+        Self::check(instance)?;
+        Ok(Io {
+            x: instance[0],
+            y: instance[1],
+            product: instance[2],
+            sum: instance[3],
+        })
+    }
+}
+
+#[derive(Default)]
+struct IoCircuit;
+
+impl<F: FieldExt> Circuit<F> for IoCircuit {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        IoCircuit
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        MyChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+        let row = |name| Io::<F>::row(name).unwrap();
+
+        let x = chip.load_public(layouter.namespace(|| "load x"), row("x"))?;
+        let y = chip.load_public(layouter.namespace(|| "load y"), row("y"))?;
+        let product = chip.mul(layouter.namespace(|| "x * y"), x.clone(), y.clone())?;
+        let sum = chip.add(layouter.namespace(|| "x + y"), x, y)?;
+
+        chip.expose_public(
+            layouter.namespace(|| "expose product"),
+            product,
+            row("product"),
+        )?;
+        chip.expose_public(layouter.namespace(|| "expose sum"), sum, row("sum"))
+    }
+}
+
+fn io(x: u64, y: u64, product: u64, sum: u64) -> Io<Fp> {
+    Io {
+        x: Fp::from(x),
+        y: Fp::from(y),
+        product: Fp::from(product),
+        sum: Fp::from(sum),
+    }
+}
+
+#[test]
+fn public_inputs_and_outputs() {
+    let instance = io(6, 7, 42, 13).to_instance();
+    let prover = MockProver::run(4, &IoCircuit, vec![instance]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    for wrong in [io(6, 7, 42, 14), io(6, 8, 42, 13)] {
+        let prover = MockProver::run(4, &IoCircuit, vec![wrong.to_instance()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[test]
+fn instance_round_trips() {
+    let public = MyPublicInputs::new(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    assert_eq!(public.d, Fp::from(230));
+    assert_eq!(
+        public.to_instance(),
+        MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3))
+    );
+    assert_eq!(
+        MyPublicInputs::from_instance(&public.to_instance()),
+        Ok(public)
+    );
+
+    assert_eq!(MyPublicInputs::<Fp>::row("d"), Ok(MyPublicInputs::<Fp>::D));
+    assert_eq!(MyPublicInputs::<Fp>::row("H"), Ok(MyPublicInputs::<Fp>::H));
+    assert_eq!(
+        MyPublicInputs::<Fp>::row("e").unwrap_err().to_string(),
+        "unknown public input `e`, expected one of d, H, constant"
+    );
+}
+
+#[test]
+fn length_mismatch_is_descriptive() {
+    let error = MyPublicInputs::from_instance(&[Fp::from(230)]).unwrap_err();
+    assert_eq!(
+        error,
+        InstanceError::Length {
+            expected: &["d", "H", "constant"],
+            found: 1
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "expected 3 public inputs (d, H, constant), found 1"
+    );
+}
+
+#[test]
+fn prove_and_verify_check_the_instance_length() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let (a, b, c) = (Fp::from(10), Fp::from(5), Fp::from(3));
+    let public = MyCircuit::public_inputs(Fp::from(2), a, b, c);
+    let circuit = MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(a),
+        b: Value::known(b),
+        c: Value::known(c),
+    };
+
+    assert!(matches!(
+        prove(&params, &pk, circuit.clone(), &public[..1]),
+        Err(ProveError::Instance(InstanceError::Length { found: 1, .. }))
+    ));
+
+    let proof = prove(&params, &pk, circuit, &public).unwrap();
+    let mut extra = public.clone();
+    extra.push(Fp::zero());
+    assert!(matches!(
+        verify(&params, pk.get_vk(), &proof, &extra),
+        Err(VerifyError::Instance(InstanceError::Length {
+            found: 4,
+            ..
+        }))
+    ));
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "layout")]
+
+use halo2_hi::{
+    layout_dot, layout_svg, render_layout, ConstantMode, LayoutError, LayoutFormat, LayoutOptions,
+    MyCircuit,
+};
+use halo2_proofs::pasta::Fp;
+use std::{fs, path::Path};
+
+fn circuit() -> (u32, MyCircuit<Fp>) {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    (k, MyCircuit::empty(ConstantMode::Fixed, Fp::from(2)))
+}
+
+#[test]
+fn svg_has_the_requested_size_and_labels() {
+    let (k, circuit) = circuit();
+    let options = LayoutOptions {
+        width: 640,
+        height: 480,
+        title: Some("MyCircuit".to_string()),
+        ..LayoutOptions::default()
+    };
+    let svg = layout_svg(k, &circuit, &options).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("width=\"640\" height=\"480\""));
+    assert!(svg.contains(">\nMyCircuit\n</text>"));
+    assert!(svg.contains(">\nload private\n</text>"));
+
+    let unlabelled = LayoutOptions {
+        show_labels: false,
+        ..options
+    };
+    let svg = layout_svg(k, &circuit, &unlabelled).unwrap();
+    assert!(!svg.contains(">\nload private\n</text>"));
+}
+
+#[test]
+fn circuits_that_do_not_fit_are_an_error() {
+    let (k, circuit) = circuit();
+    let error = layout_svg(k - 1, &circuit, &LayoutOptions::default()).unwrap_err();
+    assert!(matches!(error, LayoutError::Synthesis(_)), "{:?}", error);
+    assert!(error.to_string().starts_with("cannot lay out the circuit"));
+}
+
+#[test]
+fn view_zooms_into_the_layout() {
+    let (k, circuit) = circuit();
+    let full = layout_svg(k, &circuit, &LayoutOptions::default()).unwrap();
+    let top = LayoutOptions {
+        view_width: Some(0..2),
+        view_height: Some(0..16),
+        ..LayoutOptions::default()
+    };
+    let top = layout_svg(k, &circuit, &top).unwrap();
+    assert_ne!(full, top);
+}
+
+#[test]
+fn images_are_written_by_extension() {
+    let (k, circuit) = circuit();
+    let dir = std::env::temp_dir().join(format!("halo2_hi-layout-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let png = dir.join("layout.png");
+    render_layout(k, &circuit, &LayoutOptions::default(), &png).unwrap();
+    assert!(fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+
+    let svg = dir.join("layout.svg");
+    render_layout(k, &circuit, &LayoutOptions::default(), &svg).unwrap();
+    assert!(fs::read_to_string(&svg).unwrap().starts_with("<svg"));
+
+    assert_eq!(
+        render_layout(
+            k,
+            &circuit,
+            &LayoutOptions::default(),
+            &dir.join("layout.gif")
+        ),
+        Err(LayoutError::UnknownFormat)
+    );
+    assert_eq!(
+        LayoutFormat::from_path(Path::new("a/b.svg")),
+        Some(LayoutFormat::Svg)
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn dot_graph_has_the_namespaces() {
+    let (_, circuit) = circuit();
+    let dot = layout_dot(&circuit);
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains("[label=\"load a\";]"));
+    assert!(dot.contains("[label=\"a * a + b * c\";]"));
+}
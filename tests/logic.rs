@@ -0,0 +1,95 @@
+mod common;
+
+use common::{assert_gate_fails, Op, OpCircuit};
+use halo2_proofs::{
+    circuit::Value,
+    dev::{MockProver, VerifyFailure},
+    pasta::Fp,
+};
+
+fn run(op: Op, inputs: [u64; 3], out: Option<u64>) -> Result<(), Vec<VerifyFailure>> {
+    common::run(4, op, inputs.map(Fp::from), out.map(Fp::from))
+}
+
+/// Checks every boolean input pair of a binary gate against `table`.
+fn check_truth_table(op: Op, table: impl Fn(bool, bool) -> bool) {
+    for a in [0, 1] {
+        for b in [0, 1] {
+            let out = table(a == 1, b == 1) as u64;
+            assert_eq!(
+                run(op, [a, b, 0], Some(out)),
+                Ok(()),
+                "{:?}({}, {})",
+                op,
+                a,
+                b
+            );
+            assert!(
+                run(op, [a, b, 0], Some(1 - out)).is_err(),
+                "{:?}({}, {}) != {}",
+                op,
+                a,
+                b,
+                1 - out
+            );
+        }
+    }
+}
+
+#[test]
+fn assert_bool() {
+    assert_eq!(run(Op::AssertBool, [0, 0, 0], None), Ok(()));
+    assert_eq!(run(Op::AssertBool, [1, 0, 0], None), Ok(()));
+    assert_gate_fails(run(Op::AssertBool, [2, 0, 0], None), "bool");
+}
+
+#[test]
+fn and() {
+    check_truth_table(Op::And, |a, b| a && b);
+}
+
+#[test]
+fn or() {
+    check_truth_table(Op::Or, |a, b| a || b);
+}
+
+#[test]
+fn xor() {
+    check_truth_table(Op::Xor, |a, b| a ^ b);
+}
+
+#[test]
+fn not() {
+    assert_eq!(run(Op::Not, [0, 0, 0], Some(1)), Ok(()));
+    assert_eq!(run(Op::Not, [1, 0, 0], Some(0)), Ok(()));
+    assert!(run(Op::Not, [1, 0, 0], Some(1)).is_err());
+}
+
+#[test]
+fn select() {
+    assert_eq!(run(Op::Select, [1, 7, 9], Some(7)), Ok(()));
+    assert_eq!(run(Op::Select, [0, 7, 9], Some(9)), Ok(()));
+    assert!(run(Op::Select, [1, 7, 9], Some(9)).is_err());
+    assert!(run(Op::Select, [0, 7, 9], Some(7)).is_err());
+}
+
+#[test]
+fn non_boolean_inputs_are_rejected() {
+    // The outputs follow the arithmetic formula, so only booleanity is violated.
+    assert_gate_fails(run(Op::And, [2, 1, 0], Some(2)), "and");
+    assert_gate_fails(run(Op::And, [1, 3, 0], Some(3)), "and");
+    assert_gate_fails(run(Op::Or, [2, 0, 0], Some(2)), "or");
+    assert_gate_fails(run(Op::Xor, [0, 2, 0], Some(2)), "xor");
+    assert_gate_fails(run(Op::Select, [2, 7, 9], Some(5)), "select");
+
+    let not_two = OpCircuit {
+        op: Op::Not,
+        inputs: [
+            Value::known(Fp::from(2)),
+            Value::known(Fp::zero()),
+            Value::known(Fp::zero()),
+        ],
+    };
+    let prover = MockProver::run(4, &not_two, vec![vec![-Fp::one()]]).unwrap();
+    assert_gate_fails(prover.verify(), "not");
+}
@@ -0,0 +1,86 @@
+use halo2_hi::{merkle_root, MerkleCircuit, MerkleTree};
+use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+const K: u32 = 9;
+
+fn tree() -> MerkleTree<Fp> {
+    let leaves: Vec<_> = (1..=6).map(|i| Fp::from(i * 111)).collect();
+    MerkleTree::new(3, &leaves)
+}
+
+fn verify(circuit: &MerkleCircuit<Fp>, root: Fp) -> bool {
+    let prover = MockProver::run(K, circuit, vec![vec![root]]).unwrap();
+    prover.verify().is_ok()
+}
+
+#[test]
+fn every_leaf_is_a_member() {
+    let tree = tree();
+    for index in 0..8 {
+        let leaf = if index < 6 {
+            Fp::from((index as u64 + 1) * 111)
+        } else {
+            Fp::zero()
+        };
+        let path = tree.path(index);
+        assert_eq!(path.len(), 3);
+        assert_eq!(merkle_root(leaf, &path), tree.root());
+        assert!(
+            verify(&MerkleCircuit::new(leaf, &path), tree.root()),
+            "leaf {}",
+            index
+        );
+    }
+}
+
+#[test]
+fn depth_is_configurable() {
+    for depth in [0, 1, 4] {
+        let leaves: Vec<_> = (0..1u64 << depth).map(Fp::from).collect();
+        let tree = MerkleTree::new(depth, &leaves);
+        let index = leaves.len() - 1;
+        let circuit = MerkleCircuit::new(leaves[index], &tree.path(index));
+        assert!(verify(&circuit, tree.root()), "depth {}", depth);
+    }
+}
+
+#[test]
+fn non_members_are_rejected() {
+    let tree = tree();
+    let path = tree.path(2);
+    assert!(!verify(
+        &MerkleCircuit::new(Fp::from(999), &path),
+        tree.root()
+    ));
+    assert!(!verify(
+        &MerkleCircuit::new(Fp::from(333), &path),
+        tree.root() + Fp::one()
+    ));
+}
+
+#[test]
+fn wrong_directions_are_rejected() {
+    let tree = tree();
+    let mut path = tree.path(2);
+    assert!(verify(
+        &MerkleCircuit::new(Fp::from(333), &path),
+        tree.root()
+    ));
+
+    path[0].1 = !path[0].1;
+    assert!(!verify(
+        &MerkleCircuit::new(Fp::from(333), &path),
+        tree.root()
+    ));
+}
+
+#[test]
+fn wrong_siblings_are_rejected() {
+    let tree = tree();
+    let mut path = tree.path(5);
+    path[1].0 += Fp::one();
+    assert!(!verify(
+        &MerkleCircuit::new(Fp::from(666), &path),
+        tree.root()
+    ));
+}
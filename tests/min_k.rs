@@ -0,0 +1,119 @@
+use halo2_hi::{
+    min_k, prove, setup, verify, ConstantMode, Expr, ExprCircuit, MyChip, MyCircuit, MyConfig,
+    RANGE_TABLE_BITS,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use std::collections::BTreeMap;
+
+/// Checks that `circuit` fits in `k` rows but not in `k - 1`.
+fn assert_min_k<C: Circuit<Fp>>(circuit: &C, instance: Vec<Vec<Fp>>, k: u32) {
+    assert_eq!(min_k(circuit, &instance).unwrap(), k);
+    assert!(MockProver::run(k, circuit, instance.clone()).is_ok());
+    assert!(matches!(
+        MockProver::run(k - 1, circuit, instance),
+        Err(Error::NotEnoughRowsAvailable { .. }) | Err(Error::InstanceTooLarge)
+    ));
+}
+
+/// `x * x * ... * x` with `n` factors, which takes one region per multiplication.
+fn power(n: usize) -> ExprCircuit<Fp> {
+    let x = Expr::var("x");
+    let expr = (1..n).fold(x.clone(), |acc, _| acc * x.clone());
+    ExprCircuit::new(expr, &BTreeMap::from([("x".to_string(), Fp::one())]))
+}
+
+#[test]
+fn my_circuit_fits_its_minimal_k() {
+    for mode in [ConstantMode::Fixed, ConstantMode::Public] {
+        let k = MyCircuit::<Fp>::min_k(mode).unwrap();
+        let circuit = MyCircuit {
+            constant: Fp::from(2),
+            mode,
+            a: Value::known(Fp::from(10)),
+            b: Value::known(Fp::from(5)),
+            c: Value::known(Fp::from(3)),
+        };
+        let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+        assert_min_k(&circuit, vec![public], k);
+    }
+}
+
+#[test]
+fn minimal_k_grows_with_the_circuit() {
+    let small = min_k(&power(2), &[vec![Fp::one()]]).unwrap();
+    let large = min_k(&power(40), &[vec![Fp::one()]]).unwrap();
+    assert!(small < large);
+
+    assert_min_k(&power(2), vec![vec![Fp::one()]], small);
+    assert_min_k(&power(40), vec![vec![Fp::one()]], large);
+}
+
+#[test]
+fn minimal_k_accounts_for_public_inputs() {
+    let small = min_k(&power(2), &[vec![Fp::one()]]).unwrap();
+    let mut instance = vec![Fp::zero(); 100];
+    instance[0] = Fp::one();
+    let k = min_k(&power(2), &[instance.clone()]).unwrap();
+
+    assert!(k > small);
+    assert_min_k(&power(2), vec![instance], k);
+}
+
+#[test]
+fn setup_uses_the_minimal_k() {
+    let (params, pk) = setup(ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    assert_eq!(params.get_g().len(), 1 << k);
+
+    let circuit = MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(10)),
+        b: Value::known(Fp::from(5)),
+        c: Value::known(Fp::from(3)),
+    };
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    let proof = prove(&params, &pk, circuit, &public).unwrap();
+    assert!(verify(&params, pk.get_vk(), &proof, &public).is_ok());
+}
+
+/// Range checks a value, which loads the whole range table.
+#[derive(Default)]
+struct RangeCircuit;
+
+impl Circuit<Fp> for RangeCircuit {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        RangeCircuit
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        MyChip::configure_with_range(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+        chip.load_range_table(layouter.namespace(|| "range table"))?;
+
+        let value =
+            chip.load_private(layouter.namespace(|| "load value"), Value::known(Fp::one()))?;
+        chip.range_check(layouter.namespace(|| "range check"), value, 8)
+    }
+}
+
+#[test]
+fn minimal_k_accounts_for_lookup_tables() {
+    // The table fills 2^RANGE_TABLE_BITS rows, which leaves no room for blinding.
+    assert_min_k(&RangeCircuit, vec![vec![]], RANGE_TABLE_BITS as u32 + 1);
+}
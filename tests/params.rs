@@ -0,0 +1,86 @@
+use halo2_hi::{
+    keygen, prove, read_params, verify, write_params, ConstantMode, MyCircuit, ParamsError,
+};
+use halo2_proofs::{
+    circuit::Value,
+    pasta::{EqAffine, Fp},
+    poly::commitment::Params,
+};
+
+fn stored_params(k: u32) -> Vec<u8> {
+    let params: Params<EqAffine> = Params::new(k);
+    let mut bytes = vec![];
+    write_params(&params, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn round_trip_preserves_params() {
+    let params: Params<EqAffine> = Params::new(5);
+    let mut bytes = vec![];
+    write_params(&params, &mut bytes).unwrap();
+
+    let reloaded = read_params(&mut &bytes[..], 5).unwrap();
+    let mut original = vec![];
+    let mut copy = vec![];
+    params.write(&mut original).unwrap();
+    reloaded.write(&mut copy).unwrap();
+    assert_eq!(original, copy);
+}
+
+#[test]
+fn reloaded_params_prove_and_verify() {
+    let bytes = stored_params(8);
+    let params = read_params(&mut &bytes[..], 8).unwrap();
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+
+    let circuit = MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(10)),
+        b: Value::known(Fp::from(5)),
+        c: Value::known(Fp::from(3)),
+    };
+    let proof = prove(&params, &pk, circuit, &public).unwrap();
+    assert!(verify(&params, pk.get_vk(), &proof, &public).is_ok());
+}
+
+#[test]
+fn wrong_k_is_rejected() {
+    let bytes = stored_params(4);
+    assert!(matches!(
+        read_params(&mut &bytes[..], 5),
+        Err(ParamsError::WrongK {
+            expected: 5,
+            found: 4
+        })
+    ));
+}
+
+#[test]
+fn corrupted_params_are_rejected() {
+    let mut bytes = stored_params(4);
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    assert!(matches!(
+        read_params(&mut &bytes[..], 4),
+        Err(ParamsError::ChecksumMismatch)
+    ));
+
+    let mut bytes = stored_params(4);
+    bytes[0] = b'x';
+    assert!(matches!(
+        read_params(&mut &bytes[..], 4),
+        Err(ParamsError::BadMagic)
+    ));
+}
+
+#[test]
+fn truncated_params_are_rejected() {
+    let bytes = stored_params(4);
+    assert!(matches!(
+        read_params(&mut &bytes[..20], 4),
+        Err(ParamsError::Io(_))
+    ));
+}
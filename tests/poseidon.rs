@@ -0,0 +1,128 @@
+use halo2_hi::{poseidon_hash, poseidon_permute, MyChip, MyConfig, PoseidonChip, PoseidonConfig};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// Hashes private `inputs` and exposes the digest.
+#[derive(Clone)]
+struct HashCircuit {
+    inputs: Vec<Value<Fp>>,
+}
+
+impl Circuit<Fp> for HashCircuit {
+    type Config = (MyConfig, PoseidonConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        HashCircuit {
+            inputs: vec![Value::unknown(); self.inputs.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let chip = MyChip::configure(meta, advice, instance, constant);
+
+        let state = [(); 3].map(|_| meta.advice_column());
+        let round_constants = [(); 3].map(|_| meta.fixed_column());
+        (chip, PoseidonChip::configure(meta, state, round_constants))
+    }
+
+    fn synthesize(
+        &self,
+        (config, poseidon): Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+        let poseidon = PoseidonChip::new(poseidon);
+
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                chip.load_private(layouter.namespace(|| format!("load {}", i)), *value)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let digest = poseidon.hash(layouter.namespace(|| "hash"), &inputs)?;
+
+        chip.expose_public(layouter.namespace(|| "expose digest"), digest, 0)
+    }
+}
+
+fn circuit(inputs: &[Fp]) -> HashCircuit {
+    HashCircuit {
+        inputs: inputs.iter().copied().map(Value::known).collect(),
+    }
+}
+
+fn values(n: u64) -> Vec<Fp> {
+    (1..=n).map(|i| Fp::from(i * 1_000_003)).collect()
+}
+
+#[test]
+fn circuit_matches_native_hash() {
+    for n in 1..=5 {
+        let inputs = values(n);
+        let digest = poseidon_hash(&inputs);
+        let prover = MockProver::run(9, &circuit(&inputs), vec![vec![digest]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "{} inputs", n);
+    }
+}
+
+#[test]
+fn wrong_digest_is_rejected() {
+    let inputs = values(3);
+    let digest = poseidon_hash(&inputs) + Fp::one();
+    let prover = MockProver::run(9, &circuit(&inputs), vec![vec![digest]]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn digest_binds_order_length_and_values() {
+    let (a, b, c) = (Fp::from(10), Fp::from(5), Fp::from(3));
+    let digest = poseidon_hash(&[a, b, c]);
+
+    assert_ne!(digest, poseidon_hash(&[a, c, b]));
+    assert_ne!(digest, poseidon_hash(&[a, b, c + Fp::one()]));
+    // Padding with zeros does not collide, since the length is part of the state.
+    assert_ne!(poseidon_hash(&[a]), poseidon_hash(&[a, Fp::zero()]));
+    assert_ne!(poseidon_hash(&[a, b]), poseidon_hash(&[a, b, Fp::zero()]));
+}
+
+#[test]
+fn permutation_matches_the_reference_test_vector() {
+    // The first `fp::permute()` vector of halo2_gadgets
+    // `poseidon::primitives::test_vectors`, which come from the `orchard_poseidon`
+    // script of zcash-test-vectors. Limbs are little-endian.
+    let mut state = [Fp::zero(), Fp::one(), Fp::from(2)];
+    poseidon_permute(&mut state);
+    assert_eq!(
+        state,
+        [
+            Fp::from_raw([
+                0xaeb1_bc02_4aec_a456,
+                0xf7e6_9a71_d0b6_42a0,
+                0x94ef_b364_f966_240f,
+                0x2a52_6acd_0b64_b453,
+            ]),
+            Fp::from_raw([
+                0x012a_3e96_28e5_b82a,
+                0xdcd4_2e7f_bed9_dafe,
+                0x76ff_7dae_343d_5512,
+                0x13c5_d156_8b4a_a430,
+            ]),
+            Fp::from_raw([
+                0x3590_29a1_d34e_9ddd,
+                0xf7cf_dfe1_bda4_2c7b,
+                0x256f_cd59_7984_561a,
+                0x0a49_c868_c697_6544,
+            ]),
+        ]
+    );
+}
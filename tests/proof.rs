@@ -0,0 +1,224 @@
+use halo2_hi::{
+    keygen, prove, prove_many, verify, verify_batch, verify_many, ConstantMode, MyCircuit,
+    ProveError, VerifyError,
+};
+use halo2_proofs::{
+    circuit::Value,
+    pasta::{EqAffine, Fp},
+    poly::commitment::Params,
+};
+
+fn circuit(constant: u64, a: u64, b: u64, c: u64) -> MyCircuit<Fp> {
+    MyCircuit {
+        constant: Fp::from(constant),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(a)),
+        b: Value::known(Fp::from(b)),
+        c: Value::known(Fp::from(c)),
+    }
+}
+
+#[test]
+fn valid_proof_verifies() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+
+    let proof = prove(&params, &pk, circuit(2, 10, 5, 3), &public).unwrap();
+    assert!(verify(&params, pk.get_vk(), &proof, &public).is_ok());
+}
+
+#[test]
+fn tampered_public_input_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+
+    let proof = prove(&params, &pk, circuit(2, 10, 5, 3), &public).unwrap();
+    assert!(matches!(
+        verify(
+            &params,
+            pk.get_vk(),
+            &proof,
+            &[public[0] + Fp::one(), public[1], public[2]]
+        ),
+        Err(VerifyError::InvalidProof)
+    ));
+}
+
+#[test]
+fn truncated_proof_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+
+    let proof = prove(&params, &pk, circuit(2, 10, 5, 3), &public).unwrap();
+    let mut bytes = proof.into_bytes();
+    bytes.truncate(bytes.len() / 2);
+
+    assert!(matches!(
+        verify(&params, pk.get_vk(), &bytes.into(), &public),
+        Err(VerifyError::Transcript(_))
+    ));
+}
+
+#[test]
+fn wrong_commitment_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+
+    let proof = prove(&params, &pk, circuit(2, 10, 5, 3), &public).unwrap();
+    let other = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(3), Fp::from(5));
+    assert_eq!(public[0], other[0]);
+    assert!(matches!(
+        verify(&params, pk.get_vk(), &proof, &other),
+        Err(VerifyError::InvalidProof)
+    ));
+}
+
+#[test]
+fn public_constant_key_proves_every_constant() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Public, Fp::zero()).unwrap();
+
+    for constant in [2, 3] {
+        let public =
+            MyCircuit::public_inputs(Fp::from(constant), Fp::from(10), Fp::from(5), Fp::from(3));
+        let circuit = MyCircuit {
+            mode: ConstantMode::Public,
+            ..circuit(constant, 10, 5, 3)
+        };
+        let proof = prove(&params, &pk, circuit, &public).unwrap();
+        assert!(verify(&params, pk.get_vk(), &proof, &public).is_ok());
+
+        // The constant is bound by the proof like any other public input.
+        let other = MyCircuit::public_inputs(
+            Fp::from(constant + 1),
+            Fp::from(10),
+            Fp::from(5),
+            Fp::from(3),
+        );
+        assert!(matches!(
+            verify(
+                &params,
+                pk.get_vk(),
+                &proof,
+                &[public[0], public[1], other[2]]
+            ),
+            Err(VerifyError::InvalidProof)
+        ));
+    }
+}
+
+#[test]
+fn fixed_constant_must_match_the_public_input() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let public = MyCircuit::public_inputs(Fp::from(3), Fp::from(10), Fp::from(5), Fp::from(3));
+
+    let proof = prove(&params, &pk, circuit(3, 10, 5, 3), &public).unwrap();
+    assert!(matches!(
+        verify(&params, pk.get_vk(), &proof, &public),
+        Err(VerifyError::InvalidProof)
+    ));
+}
+
+fn batch(witnesses: &[(u64, u64, u64)]) -> (Vec<MyCircuit<Fp>>, Vec<Vec<Fp>>) {
+    witnesses
+        .iter()
+        .map(|&(a, b, c)| {
+            let public =
+                MyCircuit::public_inputs(Fp::from(2), Fp::from(a), Fp::from(b), Fp::from(c));
+            (circuit(2, a, b, c), public)
+        })
+        .unzip()
+}
+
+#[test]
+fn batch_proof_verifies() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let (circuits, public) = batch(&[(10, 5, 3), (1, 2, 3), (7, 0, 9)]);
+
+    let proof = prove_many(&params, &pk, &circuits, &public).unwrap();
+    assert!(verify_many(&params, pk.get_vk(), &proof, &public).is_ok());
+
+    // Every instance column is bound to its own circuit.
+    let mut swapped = public.clone();
+    swapped.swap(0, 1);
+    assert!(matches!(
+        verify_many(&params, pk.get_vk(), &proof, &swapped),
+        Err(VerifyError::InvalidProof)
+    ));
+    assert!(verify(&params, pk.get_vk(), &proof, &public[0]).is_err());
+}
+
+#[test]
+fn batch_with_invalid_witness_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let (circuits, mut public) = batch(&[(10, 5, 3), (1, 2, 3)]);
+    public[1][0] += Fp::one();
+
+    let proof = prove_many(&params, &pk, &circuits, &public).unwrap();
+    assert!(matches!(
+        verify_many(&params, pk.get_vk(), &proof, &public),
+        Err(VerifyError::InvalidProof)
+    ));
+}
+
+#[test]
+fn batch_size_mismatch_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let (circuits, public) = batch(&[(10, 5, 3), (1, 2, 3)]);
+
+    assert!(matches!(
+        prove_many(&params, &pk, &circuits, &public[..1]),
+        Err(ProveError::BatchSize {
+            circuits: 2,
+            instances: 1
+        })
+    ));
+}
+
+#[test]
+fn batch_verification_accepts_valid_proofs() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let (circuits, public) = batch(&[(10, 5, 3), (1, 2, 3), (7, 0, 9)]);
+
+    let proofs: Vec<_> = circuits
+        .into_iter()
+        .zip(public)
+        .map(|(circuit, public)| (prove(&params, &pk, circuit, &public).unwrap(), public))
+        .collect();
+    assert!(verify_batch(&params, pk.get_vk(), &proofs).is_ok());
+    assert!(verify_batch::<Vec<Fp>>(&params, pk.get_vk(), &[]).is_ok());
+}
+
+#[test]
+fn batch_verification_identifies_invalid_proofs() {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let (circuits, public) = batch(&[(10, 5, 3), (1, 2, 3), (7, 0, 9), (4, 4, 4), (2, 3, 4)]);
+
+    let mut proofs: Vec<_> = circuits
+        .into_iter()
+        .zip(public)
+        .map(|(circuit, public)| (prove(&params, &pk, circuit, &public).unwrap(), public))
+        .collect();
+    proofs[1].1[0] += Fp::one();
+    let mut bytes = proofs[3].0.clone().into_bytes();
+    bytes.truncate(bytes.len() / 2);
+    proofs[3].0 = bytes.into();
+    proofs[4].1.pop();
+
+    let failures = verify_batch(&params, pk.get_vk(), &proofs).unwrap_err();
+    let indices: Vec<_> = failures.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, [1, 3, 4]);
+    assert!(matches!(failures[0].1, VerifyError::InvalidProof));
+    assert!(matches!(failures[1].1, VerifyError::Transcript(_)));
+    assert!(matches!(failures[2].1, VerifyError::Instance(_)));
+}
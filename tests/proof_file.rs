@@ -0,0 +1,121 @@
+use halo2_hi::{
+    circuit_fingerprint, keygen, prove, verify, ConstantMode, MyCircuit, ProofFile, ProofFileError,
+    TranscriptKind,
+};
+use halo2_proofs::{
+    circuit::Value,
+    pasta::{EqAffine, Fp},
+    poly::commitment::Params,
+};
+
+fn proof_file() -> (Params<EqAffine>, ProofFile) {
+    let params: Params<EqAffine> = Params::new(8);
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let circuit = MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(10)),
+        b: Value::known(Fp::from(5)),
+        c: Value::known(Fp::from(3)),
+    };
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    let proof = prove(&params, &pk, circuit, &public).unwrap();
+
+    let file = ProofFile {
+        transcript: TranscriptKind::Blake2b,
+        circuit_id: circuit_fingerprint::<MyCircuit<Fp>>(),
+        k: 8,
+        instances: public,
+        proof,
+    };
+    (params, file)
+}
+
+#[test]
+fn round_trip_verifies() {
+    let (params, file) = proof_file();
+    let id = circuit_fingerprint::<MyCircuit<Fp>>();
+
+    let parsed = ProofFile::from_bytes(&file.to_bytes(), &id).unwrap();
+    assert_eq!(parsed, file);
+
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    assert!(verify(&params, pk.get_vk(), &parsed.proof, &parsed.instances).is_ok());
+}
+
+#[test]
+fn truncated_files_are_rejected() {
+    let (_, file) = proof_file();
+    let id = circuit_fingerprint::<MyCircuit<Fp>>();
+    let bytes = file.to_bytes();
+
+    for len in 0..bytes.len() {
+        assert_eq!(
+            ProofFile::from_bytes(&bytes[..len], &id),
+            Err(ProofFileError::Truncated),
+            "prefix of length {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn wrong_version_is_rejected() {
+    let (_, file) = proof_file();
+    let id = circuit_fingerprint::<MyCircuit<Fp>>();
+    let mut bytes = file.to_bytes();
+    bytes[8] = 2;
+
+    assert_eq!(
+        ProofFile::from_bytes(&bytes, &id),
+        Err(ProofFileError::UnsupportedVersion(2))
+    );
+}
+
+#[test]
+fn wrong_circuit_is_rejected() {
+    let (_, file) = proof_file();
+    let mut other = circuit_fingerprint::<MyCircuit<Fp>>();
+    other[0] ^= 1;
+
+    assert_eq!(
+        ProofFile::from_bytes(&file.to_bytes(), &other),
+        Err(ProofFileError::WrongCircuit)
+    );
+}
+
+#[test]
+fn malformed_fields_are_rejected() {
+    let (_, file) = proof_file();
+    let id = circuit_fingerprint::<MyCircuit<Fp>>();
+    let bytes = file.to_bytes();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] ^= 1;
+    assert_eq!(
+        ProofFile::from_bytes(&bad_magic, &id),
+        Err(ProofFileError::BadMagic)
+    );
+
+    let mut bad_transcript = bytes.clone();
+    bad_transcript[10] = 7;
+    assert_eq!(
+        ProofFile::from_bytes(&bad_transcript, &id),
+        Err(ProofFileError::UnsupportedTranscript(7))
+    );
+
+    // The first instance starts after magic, version, transcript, circuit id, k and count.
+    let mut bad_instance = bytes.clone();
+    bad_instance[51..83].copy_from_slice(&[0xff; 32]);
+    assert_eq!(
+        ProofFile::from_bytes(&bad_instance, &id),
+        Err(ProofFileError::InvalidInstance(0))
+    );
+
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert_eq!(
+        ProofFile::from_bytes(&trailing, &id),
+        Err(ProofFileError::TrailingBytes)
+    );
+}
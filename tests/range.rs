@@ -0,0 +1,149 @@
+mod common;
+
+use halo2_hi::{CircuitStats, MyChip, MyConfig, Trace, RANGE_TABLE_BITS};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::{MockProver, VerifyFailure},
+    pasta::Fp,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// Range checks `value` to `bits` bits.
+#[derive(Clone)]
+struct RangeCircuit<F: FieldExt> {
+    value: Value<F>,
+    bits: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for RangeCircuit<F> {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        RangeCircuit {
+            value: Value::unknown(),
+            bits: self.bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        common::configure_with_range(meta)
+    }
+
+    fn synthesize(&self, config: MyConfig, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = MyChip::new(config);
+        chip.load_range_table(layouter.namespace(|| "range table"))?;
+
+        let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        chip.range_check(layouter.namespace(|| "range check"), value, self.bits)
+    }
+}
+
+fn run(value: Fp, bits: usize) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = RangeCircuit {
+        value: Value::known(value),
+        bits,
+    };
+    MockProver::run(RANGE_TABLE_BITS as u32 + 1, &circuit, vec![vec![]])
+        .unwrap()
+        .verify()
+}
+
+fn pow2(bits: usize) -> Fp {
+    Fp::from(2).pow(&[bits as u64, 0, 0, 0])
+}
+
+fn assert_lookup_fails(result: Result<(), Vec<VerifyFailure>>) {
+    let failures = result.unwrap_err();
+    assert!(!failures.is_empty());
+    for failure in failures {
+        assert!(
+            matches!(failure, VerifyFailure::Lookup { .. }),
+            "unexpected failure: {}",
+            failure
+        );
+    }
+}
+
+#[test]
+fn values_in_range_pass() {
+    for bits in [1, 4, 8, 10, 16, 64, 100] {
+        assert_eq!(run(Fp::zero(), bits), Ok(()), "0 in {} bits", bits);
+        assert_eq!(run(Fp::one(), bits), Ok(()), "1 in {} bits", bits);
+        assert_eq!(
+            run(pow2(bits) - Fp::one(), bits),
+            Ok(()),
+            "max in {} bits",
+            bits
+        );
+    }
+    assert_eq!(run(Fp::from(0x1234_5678), 32), Ok(()));
+}
+
+#[test]
+fn values_just_out_of_range_fail_the_lookup() {
+    for bits in [1, 4, 8, 10, 16, 64, 100] {
+        assert_lookup_fails(run(pow2(bits), bits));
+        assert_lookup_fails(run(pow2(bits) + Fp::from(5), bits));
+    }
+}
+
+#[test]
+fn large_field_elements_fail_the_lookup() {
+    assert_lookup_fails(run(-Fp::one(), 64));
+    assert_lookup_fails(run(-Fp::from(1 << 10), 16));
+    assert_lookup_fails(run(pow2(200), 128));
+}
+
+#[test]
+fn unloaded_table_rejects_nonzero_values() {
+    #[derive(Clone)]
+    struct NoTable(Value<Fp>);
+
+    impl Circuit<Fp> for NoTable {
+        type Config = MyConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            NoTable(Value::unknown())
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            RangeCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: MyConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = MyChip::new(config);
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.0)?;
+            chip.range_check(layouter.namespace(|| "range check"), value, 8)
+        }
+    }
+
+    let prover = MockProver::run(4, &NoTable(Value::known(Fp::from(3))), vec![vec![]]).unwrap();
+    assert_lookup_fails(prover.verify());
+}
+
+#[test]
+fn the_range_table_is_configured_on_request() {
+    let circuit = RangeCircuit {
+        value: Value::known(Fp::from(5)),
+        bits: 12,
+    };
+    let k = RANGE_TABLE_BITS as u32 + 1;
+    let stats = CircuitStats::measure(k, &circuit, &[vec![]]).unwrap();
+    assert_eq!(stats.lookups, 1);
+
+    let trace = Trace::new(k, &circuit, &[vec![]]).unwrap();
+    let selectors = &trace.selectors[trace.selectors.len() - 3..];
+    assert_eq!(selectors, ["s_lt", "s_lookup", "s_decompose"]);
+    let decompose = trace.gates.iter().find(|gate| gate.name == "decompose");
+    assert_eq!(
+        decompose.unwrap().constraints[0].1,
+        "(a1 - a0 - (256 * a1[+1])) * s_decompose"
+    );
+}
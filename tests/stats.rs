@@ -0,0 +1,102 @@
+use halo2_hi::{
+    prove, setup, CircuitStats, ConstantMode, Expr, ExprCircuit, InspectError, MyCircuit,
+    MyPublicInputs, PublicInputs,
+};
+use halo2_proofs::{circuit::Value, pasta::Fp, plonk::Error};
+use std::collections::BTreeMap;
+
+fn circuit(a: u64, b: u64, c: u64) -> MyCircuit<Fp> {
+    MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(a)),
+        b: Value::known(Fp::from(b)),
+        c: Value::known(Fp::from(c)),
+    }
+}
+
+fn public(a: u64, b: u64, c: u64) -> Vec<Fp> {
+    MyCircuit::public_inputs(Fp::from(2), Fp::from(a), Fp::from(b), Fp::from(c))
+}
+
+#[test]
+fn my_circuit_stats() {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let stats = CircuitStats::measure(k, &circuit(10, 5, 3), &[public(10, 5, 3)]).unwrap();
+
+    assert_eq!(stats.k, k);
+    assert_eq!(stats.advice_columns, 5);
+    assert_eq!(stats.instance_columns, 1);
+    assert_eq!(stats.lookups, 0);
+    assert!(stats.regions > 0);
+    assert!(stats.rows_used <= stats.usable_rows);
+    assert!(stats.usable_rows < 1 << k);
+    // The constant column and the Poseidon round constants.
+    assert!(stats.fixed_columns >= 4);
+    assert!(stats.selectors > 0);
+    // The advice columns, the constant column and the instance column.
+    assert_eq!(stats.permutation_columns, 7);
+    assert!(stats.max_degree >= 3);
+    assert!(stats.verifier_msm_size > 1 << k);
+}
+
+#[test]
+fn stats_do_not_depend_on_the_witness() {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let instance = [vec![Fp::zero(); MyPublicInputs::<Fp>::NAMES.len()]];
+
+    let with_witness = CircuitStats::measure(k, &circuit(10, 5, 3), &[public(10, 5, 3)]).unwrap();
+    let without = CircuitStats::measure(
+        k,
+        &MyCircuit::empty(ConstantMode::Fixed, Fp::from(2)),
+        &instance,
+    )
+    .unwrap();
+    assert_eq!(with_witness, without);
+}
+
+#[test]
+fn estimated_proof_size_matches_a_real_proof() {
+    let (params, pk) = setup(ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let proof = prove(&params, &pk, circuit(10, 5, 3), &public(10, 5, 3)).unwrap();
+
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let stats = CircuitStats::measure(k, &circuit(10, 5, 3), &[public(10, 5, 3)]).unwrap();
+    assert_eq!(stats.proof_size, proof.as_bytes().len());
+}
+
+#[test]
+fn stats_grow_with_the_circuit() {
+    let x = Expr::var("x");
+    let stats = |n: usize| {
+        let expr = (1..n).fold(x.clone(), |acc, _| acc * x.clone());
+        let circuit = ExprCircuit::new(expr, &BTreeMap::from([("x".to_string(), Fp::one())]));
+        CircuitStats::measure(8, &circuit, &[vec![Fp::one()]]).unwrap()
+    };
+
+    let (small, large) = (stats(2), stats(20));
+    assert!(small.regions < large.regions);
+    assert!(small.rows_used < large.rows_used);
+    assert_eq!(small.advice_columns, large.advice_columns);
+    assert_eq!(small.proof_size, large.proof_size);
+}
+
+#[test]
+fn too_small_k_is_rejected() {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    assert!(matches!(
+        CircuitStats::measure(k - 1, &circuit(10, 5, 3), &[public(10, 5, 3)]),
+        Err(InspectError::Synthesis(
+            Error::NotEnoughRowsAvailable { .. }
+        ))
+    ));
+}
+
+#[test]
+fn wrong_instance_count_is_rejected() {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    assert!(matches!(
+        CircuitStats::measure(k, &circuit(10, 5, 3), &[]),
+        Err(InspectError::Synthesis(Error::InvalidInstances))
+    ));
+}
@@ -0,0 +1,136 @@
+use halo2_hi::{ConstantMode, Expr, ExprCircuit, MyCircuit, MyPublicInputs, PublicInputs, Trace};
+use halo2_proofs::{circuit::Value, pasta::Fp};
+use std::collections::BTreeMap;
+
+fn trace() -> Trace {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let circuit = MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(10)),
+        b: Value::known(Fp::from(5)),
+        c: Value::known(Fp::from(3)),
+    };
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    Trace::new(k, &circuit, &[public]).unwrap()
+}
+
+#[test]
+fn gates_are_rendered_as_polynomials() {
+    let trace = trace();
+    let gate = |name: &str| {
+        trace
+            .gates
+            .iter()
+            .find(|gate| gate.name == name)
+            .unwrap()
+            .constraints
+            .iter()
+            .map(|(_, poly)| poly.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(gate("mul"), ["(a0 * a1 - a0[+1]) * s_mul"]);
+    assert_eq!(gate("sub"), ["(a0 - a1 - a0[+1]) * s_sub"]);
+    assert_eq!(gate("bool"), ["(a0 * (1 - a0)) * s_bool"]);
+    assert_eq!(
+        gate("select")[1],
+        "(a0 * (a1 - a0[+1]) + a0[+1] - a1[+1]) * s_select"
+    );
+}
+
+#[test]
+fn columns_and_permutation_are_named() {
+    let trace = trace();
+    assert_eq!(trace.advice_columns, ["a0", "a1", "a2", "a3", "a4"]);
+    assert_eq!(trace.instance_columns, ["i0"]);
+    assert_eq!(&trace.selectors[..2], ["s_mul", "s_add"]);
+    assert!(trace.selectors.iter().all(|name| name.starts_with("s_")));
+    // The Poseidon round constant columns are not equality-enabled.
+    assert_eq!(
+        trace.permutation,
+        ["a0", "a1", "a2", "a3", "a4", "f0", "i0"]
+    );
+}
+
+#[test]
+fn regions_list_rows_and_cells() {
+    let trace = trace();
+    let load_a = &trace.regions[0];
+    assert_eq!(load_a.namespace, ["load a"]);
+    assert_eq!(load_a.rows, Some((0, 0)));
+    assert_eq!(load_a.cells[0].cell, "a0@0");
+    assert_eq!(load_a.cells[0].value.as_deref(), Some("10"));
+    assert_eq!(load_a.cells[0].annotation, "private input");
+
+    let mul = trace
+        .regions
+        .iter()
+        .find(|region| region.selectors.contains(&("s_mul".to_string(), 4)))
+        .unwrap();
+    assert_eq!(mul.rows, Some((4, 5)));
+    assert!(mul
+        .cells
+        .iter()
+        .any(|cell| cell.cell == "a0@5" && cell.value.as_deref() == Some("100")));
+
+    // The constant 2 is loaded from the fixed column and copied out.
+    assert_eq!(trace.constants[0].cell, "f0@0");
+    assert!(trace
+        .copies
+        .contains(&("f0@0".to_string(), "a0@3".to_string())));
+
+    let text = trace.to_string();
+    assert!(text.contains("    (a0 * a1 - a0[+1]) * s_mul\n"));
+    assert!(
+        text.contains("  #0 load a / load private: rows 0..=0\n    a0@0 = 10  (private input)\n")
+    );
+    assert!(text.contains("s_partial_round@6..=61"));
+}
+
+#[test]
+fn negative_values_are_written_as_such() {
+    let expr = Expr::var("x") - Expr::var("y");
+    let inputs = BTreeMap::from([
+        ("x".to_string(), Fp::from(1)),
+        ("y".to_string(), Fp::from(3)),
+    ]);
+    let circuit = ExprCircuit::new(expr, &inputs);
+    let trace = Trace::new(6, &circuit, &[vec![-Fp::from(2)]]).unwrap();
+
+    let values: Vec<_> = trace
+        .regions
+        .iter()
+        .flat_map(|region| &region.cells)
+        .filter_map(|cell| cell.value.as_deref())
+        .collect();
+    assert!(values.contains(&"-2"));
+}
+
+#[test]
+fn trace_without_a_witness_has_no_values() {
+    let k = MyCircuit::<Fp>::min_k(ConstantMode::Fixed).unwrap();
+    let circuit = MyCircuit::empty(ConstantMode::Fixed, Fp::from(2));
+    let instance = vec![Fp::zero(); MyPublicInputs::<Fp>::NAMES.len()];
+    let empty = Trace::new(k, &circuit, &[instance]).unwrap();
+    let full = trace();
+
+    assert!(empty.regions[0].cells[0].value.is_none());
+    assert_eq!(empty.gates, full.gates);
+    assert_eq!(empty.regions.len(), full.regions.len());
+    for (empty, full) in empty.regions.iter().zip(&full.regions) {
+        assert_eq!(empty.rows, full.rows);
+    }
+}
+
+#[test]
+fn json_is_stable_and_line_oriented() {
+    let json = trace().to_json();
+    assert_eq!(json, trace().to_json());
+    assert!(json.starts_with("{\n  \"k\": 8,\n"));
+    assert!(json.contains("\n        {\"name\": \"\", \"poly\": \"(a0 * a1 - a0[+1]) * s_mul\"}\n"));
+    assert!(
+        json.contains("{\"cell\": \"a0@0\", \"annotation\": \"private input\", \"value\": \"10\"}")
+    );
+    assert!(json.contains("[\"f0@0\", \"a0@3\"]"));
+}
@@ -0,0 +1,142 @@
+use halo2_hi::{keygen, prove, verify, ConstantMode, MyCircuit, VkArtifact, VkError};
+use halo2_proofs::{
+    circuit::Value,
+    pasta::{EqAffine, Fp},
+    plonk::keygen_vk,
+    poly::commitment::Params,
+};
+
+fn exported(params: &Params<EqAffine>, constant: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    VkArtifact::generate(params, ConstantMode::Fixed, Fp::from(constant))
+        .unwrap()
+        .write(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn imported_key_verifies_proofs() {
+    let params: Params<EqAffine> = Params::new(8);
+    let bytes = exported(&params, 2);
+
+    let pk = keygen(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    let circuit = MyCircuit {
+        constant: Fp::from(2),
+        mode: ConstantMode::Fixed,
+        a: Value::known(Fp::from(10)),
+        b: Value::known(Fp::from(5)),
+        c: Value::known(Fp::from(3)),
+    };
+    let public = MyCircuit::public_inputs(Fp::from(2), Fp::from(10), Fp::from(5), Fp::from(3));
+    let proof = prove(&params, &pk, circuit, &public).unwrap();
+
+    let artifact = VkArtifact::read(&mut &bytes[..]).unwrap();
+    assert_eq!(artifact.k, 8);
+    assert_eq!(artifact.constant, Fp::from(2));
+    let vk = artifact.load(&params).unwrap();
+    assert!(verify(&params, &vk, &proof, &public).is_ok());
+}
+
+#[test]
+fn mismatched_circuit_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let mut artifact = VkArtifact::generate(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    artifact.fingerprint[0] ^= 1;
+
+    assert!(matches!(
+        artifact.load(&params),
+        Err(VkError::CircuitMismatch)
+    ));
+}
+
+#[test]
+fn mismatched_key_is_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let mut artifact = VkArtifact::generate(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+    artifact.constant = Fp::from(3);
+
+    assert!(matches!(artifact.load(&params), Err(VkError::KeyMismatch)));
+}
+
+#[test]
+fn params_with_other_k_are_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let artifact = VkArtifact::generate(&params, ConstantMode::Fixed, Fp::from(2)).unwrap();
+
+    assert!(matches!(
+        artifact.load(&Params::new(9)),
+        Err(VkError::WrongK {
+            expected: 9,
+            found: 8
+        })
+    ));
+}
+
+#[test]
+fn malformed_files_are_rejected() {
+    let params: Params<EqAffine> = Params::new(8);
+    let bytes = exported(&params, 2);
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] ^= 1;
+    assert!(matches!(
+        VkArtifact::read(&mut &bad_magic[..]),
+        Err(VkError::BadMagic)
+    ));
+
+    let mut bad_version = bytes.clone();
+    bad_version[8] = 9;
+    assert!(matches!(
+        VkArtifact::read(&mut &bad_version[..]),
+        Err(VkError::UnsupportedVersion(9))
+    ));
+
+    assert!(matches!(
+        VkArtifact::read(&mut &bytes[..bytes.len() - 1]),
+        Err(VkError::Io(_))
+    ));
+}
+
+#[test]
+fn only_fixed_constants_change_the_key() {
+    let params: Params<EqAffine> = Params::new(8);
+    // Keys straight from `keygen_vk`, since `VkArtifact` zeroes a public constant.
+    let pinned = |mode, constant| {
+        let vk = keygen_vk(&params, &MyCircuit::empty(mode, Fp::from(constant))).unwrap();
+        format!("{:?}", vk.pinned())
+    };
+
+    assert_ne!(
+        pinned(ConstantMode::Fixed, 2),
+        pinned(ConstantMode::Fixed, 3)
+    );
+    assert_eq!(
+        pinned(ConstantMode::Public, 2),
+        pinned(ConstantMode::Public, 3)
+    );
+    assert_ne!(
+        pinned(ConstantMode::Fixed, 2),
+        pinned(ConstantMode::Public, 2)
+    );
+}
+
+#[test]
+fn public_constant_key_round_trips() {
+    let params: Params<EqAffine> = Params::new(8);
+    let artifact = VkArtifact::generate(&params, ConstantMode::Public, Fp::from(2)).unwrap();
+    let mut bytes = vec![];
+    artifact.write(&mut bytes).unwrap();
+
+    let read = VkArtifact::read(&mut &bytes[..]).unwrap();
+    assert_eq!(read, artifact);
+    assert_eq!(read.mode, ConstantMode::Public);
+    assert!(read.load(&params).is_ok());
+
+    let mut bad_mode = bytes;
+    bad_mode[14] = 7;
+    assert!(matches!(
+        VkArtifact::read(&mut &bad_mode[..]),
+        Err(VkError::InvalidMode(7))
+    ));
+}